@@ -1,8 +1,27 @@
 use super::clocker::Clocker;
-use super::display::display_current_timer_status;
+use super::config::{calendar, config, time_zone};
+use super::display::{display_budget_remaining, display_current_timer_status, format_reminder};
+use super::render::OutputFormat;
 use super::utils;
-use crate::core::{database::Database, timeline::Timeline};
-use anyhow::Error;
+use crate::core::{
+    budget::{Budget, BudgetPeriod, BudgetStatus, BudgetTarget},
+    client::Client,
+    database::Database,
+    goal::Goal,
+    invoice::Invoice,
+    project::Project,
+    rate::{Rate, RateBook, RateTarget},
+    recent::{RecentDescription, rank_descriptions},
+    reminder::{Activity, ReminderEngine, RunningTimer, usual_duration},
+    search::SearchHit,
+    tag::{Tag, normalize_tag_path},
+    task_template::TaskTemplate,
+    time_slice::{OpenTimeSlice, TimeSlice},
+    timeline::{Timeline, TimelineFilter},
+};
+use anyhow::{Error, anyhow};
+use chrono::Utc;
+use rustyline::ExternalPrinter;
 use std::{
     collections::HashMap,
     sync::{
@@ -10,9 +29,27 @@ use std::{
         atomic::{AtomicBool, Ordering},
     },
     thread::JoinHandle,
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
+// 后台检查提醒的间隔
+const REMINDER_INTERVAL: Duration = Duration::from_secs(30);
+
+// 后台线程输出消息使用的 rustyline 输出器，交互模式下设置，避免打乱正在输入的命令行
+type Printer = Arc<Mutex<Option<Box<dyn ExternalPrinter + Send>>>>;
+
+// 输出后台线程的消息，没有设置输出器或输出失败时直接打印
+fn notify(printer: &Printer, message: String) {
+    let mut printer = printer.lock().expect("Get printer lock failed.");
+    if let Some(printer) = printer.as_mut()
+        && printer.print(message.clone()).is_ok()
+    {
+        return;
+    }
+    println!();
+    println!("{message}");
+}
+
 pub struct TimerStatus {
     pub start_time: u64,
     pub end_time: Option<u64>,
@@ -23,15 +60,44 @@ pub struct AppHandle {
     inner: Arc<Mutex<App>>,
     timer_handle: Option<JoinHandle<()>>,
     should_stop_flag: Arc<AtomicBool>,
+    // 提醒在后台线程中检查，AppHandle 销毁时停止
+    reminder_stop_flag: Arc<AtomicBool>,
+    printer: Printer,
 }
 
 impl AppHandle {
     pub fn new(db: Database) -> Self {
-        AppHandle {
-            inner: Arc::new(Mutex::new(App::new(db))),
+        let inner = Arc::new(Mutex::new(App::new(db)));
+        let reminder_stop_flag = Arc::new(AtomicBool::new(false));
+        let printer: Printer = Arc::new(Mutex::new(None));
+        spawn_reminder_scheduler(inner.clone(), reminder_stop_flag.clone(), printer.clone());
+        let mut app_handle = AppHandle {
+            inner,
             timer_handle: None,
             should_stop_flag: Arc::new(AtomicBool::new(false)),
+            reminder_stop_flag,
+            printer,
+        };
+        // 恢复的计时器同样需要到时自动停止，并在超出预算时发出警告
+        let restored = {
+            let app = app_handle.inner.lock().expect("Get app lock failed.");
+            app.current_timer
+                .as_ref()
+                .filter(|timer| timer.is_running())
+                .map(|timer| {
+                    let budgets = app.get_running_budgets().unwrap_or_default();
+                    (
+                        timer.get_start_time(),
+                        app.current_planned_end,
+                        budget_limits(&budgets),
+                    )
+                })
+        };
+        if let Some((start_time, planned_end, limits)) = restored {
+            let duration = planned_end.map(|planned_end| planned_end.saturating_sub(start_time));
+            app_handle.watch_timer(start_time, duration, limits);
         }
+        app_handle
     }
 
     pub fn start_timer(
@@ -41,59 +107,111 @@ impl AppHandle {
     ) -> anyhow::Result<TimerStatus> {
         let mut app = self.inner.lock().expect("Failed to get app lock");
         // 只有当前没有计时器在运行时才能启动新的计时器
-        match app.start_timer(desc) {
+        match app.start_timer(desc, duration) {
             Ok(_) => {
                 let clocker_start_time = app
                     .current_timer
                     .as_ref()
                     .expect("Get current timer failed.")
                     .get_start_time();
-                if let Some(duration) = duration {
-                    let app_inner_clone = self.inner.clone();
-                    let should_stop_flag = self.should_stop_flag.clone();
-                    self.timer_handle = Some(std::thread::spawn(move || {
-                        loop {
-                            if should_stop_flag.load(Ordering::Relaxed) {
-                                break;
-                            }
-                            let elapsed = SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .expect("Get now time failed.")
-                                .as_millis() as u64
-                                - clocker_start_time;
-
-                            if elapsed > duration {
-                                let mut app = app_inner_clone.lock().expect("Get app lock failed.");
-                                if let Err(e) = app.stop_timer() {
-                                    println!("Error stopping timer: {}", e);
-                                }
-                                if let Ok(status) = app.get_current_timer_status() {
-                                    println!("");
-                                    println!("The timer automatically stopped.",);
-                                    display_current_timer_status(&status);
-                                }
-                                break;
-                            }
-                            std::thread::sleep(std::time::Duration::from_millis(1));
-                        }
-                    }));
-                }
-                app.get_current_timer_status()
+
+                // 展示计时器相关的预算，并记录超出各预算前还能运行的时长
+                let budgets = app.get_running_budgets().unwrap_or_default();
+                display_budget_remaining(&budgets, duration);
+                let budget_limits = budget_limits(&budgets);
+
+                let status = app.get_current_timer_status();
+                drop(app);
+                self.watch_timer(clocker_start_time, duration, budget_limits);
+                status
             }
             Err(e) => Err(e),
         }
     }
 
+    // 之后后台线程的消息通过 printer 输出
+    pub fn set_printer(&self, printer: impl ExternalPrinter + Send + 'static) {
+        *self.printer.lock().expect("Get printer lock failed.") = Some(Box::new(printer));
+    }
+
+    // 在后台线程中监视计时器，到达指定时长时自动停止，超出预算时发出警告
+    fn watch_timer(
+        &mut self,
+        clocker_start_time: u64,
+        duration: Option<u64>,
+        mut budget_limits: Vec<(String, u64)>,
+    ) {
+        if duration.is_some() || !budget_limits.is_empty() {
+            let app_inner_clone = self.inner.clone();
+            // 每个计时器使用独立的停止标记，避免后台线程错过停止信号
+            self.should_stop_flag = Arc::new(AtomicBool::new(false));
+            let should_stop_flag = self.should_stop_flag.clone();
+            let printer = self.printer.clone();
+            self.timer_handle = Some(std::thread::spawn(move || {
+                loop {
+                    if should_stop_flag.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let elapsed = (SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .expect("Get now time failed.")
+                        .as_millis() as u64)
+                        .saturating_sub(clocker_start_time);
+
+                    budget_limits.retain(|(label, limit)| {
+                        if elapsed > *limit {
+                            notify(
+                                &printer,
+                                format!(
+                                    "Warning: the running timer exceeded the budget of {label}."
+                                ),
+                            );
+                            return false;
+                        }
+                        true
+                    });
+
+                    if let Some(duration) = duration
+                        && elapsed > duration
+                    {
+                        let mut app = app_inner_clone.lock().expect("Get app lock failed.");
+                        if let Err(e) = app.stop_timer() {
+                            println!("Error stopping timer: {}", e);
+                        }
+                        if let Ok(status) = app.get_current_timer_status() {
+                            println!();
+                            println!("The timer automatically stopped.",);
+                            display_current_timer_status(&status, OutputFormat::Table);
+                        }
+                        break;
+                    }
+                    // 休眠到下一个预算或预定时长到达，停止计时器时通过 unpark 提前唤醒
+                    let Some(next_limit) = budget_limits
+                        .iter()
+                        .map(|(_, limit)| *limit)
+                        .chain(duration)
+                        .min()
+                    else {
+                        break;
+                    };
+                    std::thread::park_timeout(std::time::Duration::from_millis(
+                        next_limit.saturating_sub(elapsed) + 1,
+                    ));
+                }
+            }));
+        }
+    }
+
     pub fn stop_timer(&mut self) -> anyhow::Result<TimerStatus> {
         let mut app = self
             .inner
             .lock()
             .expect("App handle stop timer failed, can't get app lock");
-        if self.timer_handle.is_some() {
+        if let Some(timer_handle) = &self.timer_handle {
             self.should_stop_flag.store(true, Ordering::Relaxed);
+            timer_handle.thread().unpark();
         }
         let _ = app.stop_timer();
-        self.should_stop_flag.store(false, Ordering::Relaxed);
         self.timer_handle = None;
         drop(app);
         self.get_current_timer_status()
@@ -106,17 +224,15 @@ impl AppHandle {
             .get_current_timer_status()
     }
 
-    // 获取时间记录列表，可以根据标签和时间范围筛选
-    pub fn get_timeline(
-        &self,
-        tag: Option<Vec<String>>,
-        start_time: Option<u64>,
-        end_time: Option<u64>,
-    ) -> anyhow::Result<Timeline> {
-        self.inner
+    // 获取时间记录列表，可以根据标签、项目、客户和时间范围筛选
+    pub fn get_timeline(&self, filter: &TimelineFilter) -> anyhow::Result<Timeline> {
+        let mut timeline = self
+            .inner
             .lock()
             .expect("Get app lock failed")
-            .get_timeline()
+            .get_timeline()?;
+        timeline.apply_filter(filter);
+        Ok(timeline)
     }
 
     pub fn remove_time_slice(&self, id: u64) -> anyhow::Result<()> {
@@ -126,24 +242,545 @@ impl AppHandle {
             .db
             .remove_time_slice(id)
     }
+
+    // 用最近一条（或指定的）已结束记录的描述与标签开始新的计时器
+    pub fn continue_timer(&mut self, id: Option<u64>) -> anyhow::Result<TimerStatus> {
+        let (_, desc) = self
+            .inner
+            .lock()
+            .expect("Get app lock failed")
+            .get_finished_slice(id)?;
+        self.start_timer(None, desc)
+    }
+
+    // 最近一条记录在 within 毫秒内结束时重新打开它继续计时，否则以它的描述开始新的计时器
+    pub fn resume_last(&mut self, within: u64) -> anyhow::Result<TimerStatus> {
+        let mut app = self.inner.lock().expect("Get app lock failed");
+        if app.resume_last(within)? {
+            return app.get_current_timer_status();
+        }
+        drop(app);
+        self.continue_timer(None)
+    }
+
+    // 只读取正在计时的切片，不加载全部记录
+    pub fn get_open_time_slice(&self) -> anyhow::Result<Option<OpenTimeSlice>> {
+        self.with_db(|db| db.get_open_time_slice())
+    }
+
+    pub fn search_descriptions(&self, query: &str) -> anyhow::Result<Vec<SearchHit>> {
+        self.with_db(|db| db.search_descriptions(query))
+    }
+
+    // 最近开始的 limit 条记录的 ID 与描述
+    pub fn get_recent_time_slice_ids(
+        &self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(u64, Option<String>)>> {
+        self.with_db(|db| db.get_recent_time_slice_ids(limit))
+    }
+
+    // 最近 limit 条记录中用过的描述，按使用频率与距今时间排序
+    pub fn get_recent_descriptions(&self, limit: usize) -> anyhow::Result<Vec<RecentDescription>> {
+        let uses = self.with_db(|db| db.get_recent_descriptions(limit))?;
+        Ok(rank_descriptions(&uses, utils::get_current_time()))
+    }
+
+    fn with_db<T>(&self, f: impl FnOnce(&mut Database) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        f(&mut self.inner.lock().expect("Get app lock failed").db)
+    }
+
+    pub fn get_projects(&self) -> anyhow::Result<Vec<Project>> {
+        self.with_db(|db| db.get_all_projects())
+    }
+
+    pub fn has_project(&self, name: &str) -> anyhow::Result<bool> {
+        self.with_db(|db| Ok(db.find_project(name)?.is_some()))
+    }
+
+    pub fn add_project(&self, name: &str, client: Option<&str>) -> anyhow::Result<Project> {
+        self.with_db(|db| {
+            if db.find_project(name)?.is_some() {
+                return Err(anyhow!("Project '{name}' already exists"));
+            }
+            let client_id = match client {
+                Some(client) => Some(db.find_or_create_client(client)?.id),
+                None => None,
+            };
+            db.create_project(name, client_id)
+        })
+    }
+
+    pub fn rename_project(&self, name: &str, new_name: &str) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let project = find_project(db, name)?;
+            if db.find_project(new_name)?.is_some() {
+                return Err(anyhow!("Project '{new_name}' already exists"));
+            }
+            db.rename_project(project.id, new_name)
+        })
+    }
+
+    // client 为 None 时取消项目与客户的关联
+    pub fn set_project_client(&self, name: &str, client: Option<&str>) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let project = find_project(db, name)?;
+            let client_id = match client {
+                Some(client) => Some(db.find_or_create_client(client)?.id),
+                None => None,
+            };
+            db.set_project_client(project.id, client_id)
+        })
+    }
+
+    pub fn set_project_archived(&self, name: &str, archived: bool) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let project = find_project(db, name)?;
+            db.set_project_archived(project.id, archived)
+        })
+    }
+
+    pub fn remove_project(&self, name: &str) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let project = find_project(db, name)?;
+            db.remove_project(project.id)
+        })
+    }
+
+    pub fn get_clients(&self) -> anyhow::Result<Vec<Client>> {
+        self.with_db(|db| db.get_all_clients())
+    }
+
+    pub fn add_client(&self, name: &str) -> anyhow::Result<Client> {
+        self.with_db(|db| {
+            if db.find_client(name)?.is_some() {
+                return Err(anyhow!("Client '{name}' already exists"));
+            }
+            db.find_or_create_client(name)
+        })
+    }
+
+    pub fn rename_client(&self, name: &str, new_name: &str) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let client = find_client(db, name)?;
+            if db.find_client(new_name)?.is_some() {
+                return Err(anyhow!("Client '{new_name}' already exists"));
+            }
+            db.rename_client(client.id, new_name)
+        })
+    }
+
+    pub fn set_client_archived(&self, name: &str, archived: bool) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let client = find_client(db, name)?;
+            db.set_client_archived(client.id, archived)
+        })
+    }
+
+    pub fn remove_client(&self, name: &str) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let client = find_client(db, name)?;
+            db.remove_client(client.id)
+        })
+    }
+}
+
+impl AppHandle {
+    pub fn get_rates(&self) -> anyhow::Result<Vec<Rate>> {
+        self.with_db(|db| db.get_all_rates())
+    }
+
+    pub fn get_rate_book(&self) -> anyhow::Result<RateBook> {
+        self.get_rates().map(RateBook::new)
+    }
+
+    // 为项目、客户或标签设置自 effective_from 起生效的时薪
+    pub fn set_rate(
+        &self,
+        target: RateTarget,
+        name: &str,
+        hourly_rate: f64,
+        effective_from: u64,
+    ) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let target_id = match target {
+                RateTarget::Project => find_project(db, name)?.id,
+                RateTarget::Client => find_client(db, name)?.id,
+                RateTarget::Tag => {
+                    let name = normalize_tag_path(name.trim_start_matches('#'));
+                    db.find_or_create_tag(&name)?.id
+                }
+            };
+            db.set_rate(target, target_id, hourly_rate, effective_from)
+        })
+    }
+
+    pub fn remove_rate(&self, id: u64) -> anyhow::Result<()> {
+        self.with_db(|db| db.remove_rate(id))
+    }
+
+    pub fn set_billable(&self, id: u64, billable: bool) -> anyhow::Result<()> {
+        self.with_db(|db| db.set_time_slice_billable(id, billable))
+    }
+}
+
+impl AppHandle {
+    pub fn get_invoices(&self) -> anyhow::Result<Vec<Invoice>> {
+        self.with_db(|db| db.get_all_invoices())
+    }
+
+    pub fn next_invoice_number(&self) -> anyhow::Result<String> {
+        self.with_db(|db| db.next_invoice_number())
+    }
+
+    // 记录发票并将时间切片标记为已开票，before_commit 失败时不做任何修改
+    pub fn create_invoice(
+        &self,
+        invoice: &Invoice,
+        time_slice_ids: &[u64],
+        before_commit: impl FnOnce(&Invoice) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Invoice> {
+        self.with_db(|db| db.create_invoice(invoice, time_slice_ids, before_commit))
+    }
+
+    pub fn void_invoice(&self, number: &str) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let invoice = db
+                .get_all_invoices()?
+                .into_iter()
+                .find(|invoice| invoice.number == number)
+                .ok_or_else(|| anyhow!("Invoice '{number}' not found"))?;
+            if invoice.voided {
+                return Err(anyhow!("Invoice '{number}' is already voided"));
+            }
+            db.void_invoice(invoice.id)
+        })
+    }
+}
+
+impl AppHandle {
+    pub fn get_goals(&self) -> anyhow::Result<Vec<Goal>> {
+        self.with_db(|db| db.get_all_goals())
+    }
+
+    pub fn add_goal(&self, goal: &Goal) -> anyhow::Result<u64> {
+        self.with_db(|db| db.insert_goal(goal))
+    }
+
+    pub fn remove_goal(&self, id: u64) -> anyhow::Result<()> {
+        self.with_db(|db| db.remove_goal(id))
+    }
+}
+
+impl AppHandle {
+    pub fn get_tags(&self) -> anyhow::Result<Vec<Tag>> {
+        self.with_db(|db| db.get_all_tags())
+    }
+
+    // 为标签设置在报表与图表中使用的颜色，color 为 None 时清除
+    pub fn set_tag_color(&self, name: &str, color: Option<&str>) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let name = normalize_tag_path(name.trim_start_matches('#'));
+            let tag = db.find_or_create_tag(&name)?;
+            db.set_tag_color(tag.id, color)
+        })
+    }
+}
+
+impl AppHandle {
+    pub fn get_task_templates(&self) -> anyhow::Result<Vec<TaskTemplate>> {
+        self.with_db(|db| db.get_all_task_templates())
+    }
+
+    // 保存任务模板，名称前的 @ 可以省略，同名的模板会被覆盖
+    pub fn set_task_template(
+        &self,
+        name: &str,
+        duration: Option<u64>,
+        desc: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let name = name.trim_start_matches('@');
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(anyhow!("Invalid template name: '{name}'"));
+        }
+        self.with_db(|db| {
+            // s @name 同时用于模板与项目，不允许模板与项目同名
+            if db.find_project(name)?.is_some() {
+                return Err(anyhow!(
+                    "Template name '{name}' clashes with project @{name}, choose another name"
+                ));
+            }
+            ensure_project_active(db, desc)?;
+            db.set_task_template(name, duration, desc)
+        })
+    }
+
+    pub fn remove_task_template(&self, name: &str) -> anyhow::Result<()> {
+        self.with_db(|db| db.remove_task_template(name.trim_start_matches('@')))
+    }
+}
+
+impl AppHandle {
+    pub fn get_budgets(&self) -> anyhow::Result<Vec<Budget>> {
+        self.with_db(|db| db.get_all_budgets())
+    }
+
+    // 为项目或标签设置某个周期的时间预算
+    pub fn set_budget(
+        &self,
+        target: BudgetTarget,
+        name: &str,
+        period: BudgetPeriod,
+        amount: u64,
+    ) -> anyhow::Result<()> {
+        self.with_db(|db| {
+            let target_id = match target {
+                BudgetTarget::Project => find_project(db, name.trim_start_matches('@'))?.id,
+                BudgetTarget::Tag => {
+                    let name = normalize_tag_path(name.trim_start_matches('#'));
+                    db.find_or_create_tag(&name)?.id
+                }
+            };
+            db.set_budget(target, target_id, period, amount)
+        })
+    }
+
+    pub fn remove_budget(&self, id: u64) -> anyhow::Result<()> {
+        self.with_db(|db| db.remove_budget(id))
+    }
+}
+
+impl AppHandle {
+    pub fn get_time_slice(&self, id: u64) -> anyhow::Result<TimeSlice> {
+        self.with_db(|db| {
+            db.get_time_slice(id)?
+                .ok_or_else(|| anyhow!("Time record {id} not found"))
+        })
+    }
+
+    // 补录一条已结束的时间记录
+    pub fn add_time_slice(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        desc: Option<String>,
+    ) -> anyhow::Result<u64> {
+        if end_time <= start_time {
+            return Err(anyhow!("End time must be after start time"));
+        }
+        self.with_db(|db| {
+            ensure_project_active(db, desc.as_deref())?;
+            let desc_str = desc.as_deref().unwrap_or_default();
+            db.insert_time_slice_info(
+                start_time,
+                Some(end_time),
+                &utils::parse_tags(desc_str),
+                &desc,
+                &utils::parse_project(desc_str),
+                time_zone().name(),
+            )
+        })
+    }
+
+    // 修改时间记录的开始、结束时间或描述，修改描述时同时更新标签与项目
+    pub fn edit_time_slice(
+        &self,
+        id: u64,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        desc: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut app = self.inner.lock().expect("Get app lock failed");
+        app.db.atomically(|db| {
+            let mut time_slice = db
+                .get_time_slice(id)?
+                .ok_or_else(|| anyhow!("Time record {id} not found"))?;
+            // 已开票的记录不能修改时间与描述，避免与发票的金额和明细不一致
+            if time_slice.invoice_id.is_some()
+                && (start_time.is_some() || end_time.is_some() || desc.is_some())
+            {
+                return Err(anyhow!(
+                    "Time record {id} is invoiced, void the invoice first"
+                ));
+            }
+            time_slice.start_time = start_time.unwrap_or(time_slice.start_time);
+            time_slice.end_time = end_time.or(time_slice.end_time);
+            if time_slice
+                .end_time
+                .is_some_and(|end_time| end_time <= time_slice.start_time)
+            {
+                return Err(anyhow!("End time must be after start time"));
+            }
+
+            if let Some(desc) = desc {
+                ensure_project_active(db, Some(desc))?;
+            }
+            db.update_time_slice(&time_slice)?;
+            if let Some(desc) = desc {
+                db.update_time_slice_description(id, desc)?;
+                db.update_time_slice_tags(id, &utils::parse_tags(desc))?;
+                let project_id = match utils::parse_project(desc) {
+                    Some(name) => Some(db.find_or_create_project(&name)?.id),
+                    None => None,
+                };
+                db.set_time_slice_project(id, project_id)?;
+            }
+            Ok(())
+        })?;
+        // 修改的是正在计时的记录时，按数据库中的记录更新计时器
+        if app.current_slice_id == Some(id) {
+            app.reload_open_slice()?;
+        }
+        Ok(())
+    }
+}
+
+// 计时器超出各预算前还能运行的时长，已经超出的预算不再提醒
+fn budget_limits(budgets: &[(Budget, BudgetStatus)]) -> Vec<(String, u64)> {
+    budgets
+        .iter()
+        .filter(|(_, status)| status.remaining() > 0)
+        .map(|(budget, status)| (budget.label(), status.remaining() as u64))
+        .collect()
+}
+
+// 已归档的项目不能再记录时间
+fn ensure_project_active(db: &Database, desc: Option<&str>) -> anyhow::Result<()> {
+    if let Some(name) = desc.and_then(utils::parse_project)
+        && let Some(project) = db.find_project(&name)?
+        && project.archived
+    {
+        return Err(anyhow!("Project '{name}' is archived!"));
+    }
+    Ok(())
+}
+
+impl Drop for AppHandle {
+    fn drop(&mut self) {
+        self.reminder_stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+// 定期检查是否需要提醒：工作时间内长时间没有计时器，或计时器运行得比平时久得多
+fn spawn_reminder_scheduler(
+    app: Arc<Mutex<App>>,
+    should_stop_flag: Arc<AtomicBool>,
+    printer: Printer,
+) {
+    std::thread::spawn(move || {
+        let mut engine = ReminderEngine::new(utils::get_current_time());
+        let mut last_check = Instant::now();
+        while !should_stop_flag.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(200));
+            if last_check.elapsed() < REMINDER_INTERVAL {
+                continue;
+            }
+            last_check = Instant::now();
+            // 每次检查时读取配置，修改后无需重启
+            let Some(settings) = config().reminder_settings() else {
+                continue;
+            };
+            let Ok(activity) = app.lock().expect("Get app lock failed.").get_activity() else {
+                continue;
+            };
+            let now = Utc::now().with_timezone(&time_zone());
+            if let Some(reminder) = engine.check(&settings, &activity, now) {
+                notify(&printer, format_reminder(&reminder));
+            }
+        }
+    });
+}
+
+fn find_project(db: &Database, name: &str) -> anyhow::Result<Project> {
+    db.find_project(name)?
+        .ok_or_else(|| anyhow!("Project '{name}' not found"))
+}
+
+fn find_client(db: &Database, name: &str) -> anyhow::Result<Client> {
+    db.find_client(name)?
+        .ok_or_else(|| anyhow!("Client '{name}' not found"))
 }
 
 struct App {
     db: Database,
     current_timer: Option<Clocker>,
     current_desc: Option<String>,
+    // 正在计时的切片，开始计时时即写入数据库，其它进程也能读到
+    current_slice_id: Option<u64>,
+    // 指定了时长的计时器预定的结束时间
+    current_planned_end: Option<u64>,
+    // 正在运行的计时器同类记录的通常时长，避免每次检查提醒时重新计算
+    usual_cache: Option<UsualDuration>,
+}
+
+// 按计时器的开始时间与描述缓存的通常时长
+struct UsualDuration {
+    start_time: u64,
+    desc: Option<String>,
+    usual: Option<u64>,
 }
 
 impl App {
     pub fn new(db: Database) -> Self {
-        App {
+        let mut app = App {
             db,
             current_timer: None,
             current_desc: None,
+            current_slice_id: None,
+            current_planned_end: None,
+            usual_cache: None,
+        };
+        // 恢复上次退出时仍在运行的计时器
+        if let Err(e) = app.sync_open_slice() {
+            println!("Failed to restore the running timer: {e}");
         }
+        app
+    }
+
+    // 与数据库中正在计时的切片同步，计时器可能由其它进程启动或停止
+    fn sync_open_slice(&mut self) -> anyhow::Result<()> {
+        let running_id = self
+            .current_timer
+            .as_ref()
+            .filter(|timer| timer.is_running())
+            .and(self.current_slice_id);
+        match self.db.get_open_time_slice()? {
+            Some(open) if Some(open.time_slice.id) != running_id => {
+                // 预定的结束时间已过，按预定时间结束
+                if let Some(planned_end) = open.planned_end
+                    && planned_end <= utils::get_current_time()
+                {
+                    self.db.finish_time_slice(open.time_slice.id, planned_end)?;
+                    return self.sync_open_slice();
+                }
+                self.current_timer = Some(Clocker::resume(open.time_slice.start_time));
+                self.current_desc = open.desc;
+                self.current_slice_id = Some(open.time_slice.id);
+                self.current_planned_end = open.planned_end;
+            }
+            None if running_id.is_some() => {
+                // 计时器已被其它进程停止或记录已被删除
+                self.current_timer = None;
+                self.current_desc = None;
+                self.current_slice_id = None;
+                self.current_planned_end = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // 重新读取正在计时的记录，开始时间、描述或结束时间可能已被修改
+    fn reload_open_slice(&mut self) -> anyhow::Result<()> {
+        self.current_timer = None;
+        self.current_desc = None;
+        self.current_slice_id = None;
+        self.current_planned_end = None;
+        self.sync_open_slice()
     }
 
-    fn start_timer(&mut self, desc: Option<String>) -> anyhow::Result<()> {
+    fn start_timer(&mut self, desc: Option<String>, duration: Option<u64>) -> anyhow::Result<()> {
+        self.sync_open_slice()?;
         // 新增tags参数处理逻辑
         if let Some(current_timer) = &self.current_timer {
             if current_timer.is_running() {
@@ -154,54 +791,200 @@ impl App {
             }
         }
 
+        ensure_project_active(&self.db, desc.as_deref())?;
+
         let mut clocker = Clocker::new();
         clocker.start();
+        let start_time = clocker.get_start_time();
+        let planned_end = duration
+            .map(|duration| {
+                start_time
+                    .checked_add(duration)
+                    .ok_or_else(|| anyhow!("Duration is too long"))
+            })
+            .transpose()?;
+        // 开始计时时写入一条没有结束时间的记录，时区为开始计时时所在的时区
+        let desc_str = desc.as_deref().unwrap_or_default();
+        let time_slice_id = self.db.insert_open_time_slice(
+            start_time,
+            &utils::parse_tags(desc_str),
+            &desc,
+            &utils::parse_project(desc_str),
+            time_zone().name(),
+            planned_end,
+        )?;
+
         self.current_timer = Some(clocker);
         self.current_desc = desc;
+        self.current_slice_id = Some(time_slice_id);
+        self.current_planned_end = planned_end;
         println!("Timer started!");
         Ok(())
     }
 
+    // 最近一条（或指定的）已结束记录及其描述，描述中缺少的标签与项目会补在末尾
+    fn get_finished_slice(&self, id: Option<u64>) -> anyhow::Result<(TimeSlice, Option<String>)> {
+        let timeline = self.get_timeline()?;
+        let time_slice = match id {
+            Some(id) => timeline
+                .list
+                .iter()
+                .find(|slice| slice.id == id)
+                .ok_or_else(|| anyhow!("Time record {id} not found"))
+                .and_then(|slice| match slice.end_time {
+                    Some(_) => Ok(slice),
+                    None => Err(anyhow!("Time record {id} is still running")),
+                })?,
+            None => timeline
+                .list
+                .iter()
+                .filter(|slice| slice.end_time.is_some())
+                .max_by_key(|slice| slice.start_time)
+                .ok_or_else(|| anyhow!("No finished time record to continue"))?,
+        }
+        .clone();
+        let mut desc = timeline
+            .desc
+            .get(&time_slice.id)
+            .map_or("", |desc| desc.trim())
+            .to_string();
+        let desc_tags = utils::parse_tags(&desc);
+        let missing_tags: Vec<String> = timeline
+            .tags
+            .get(&time_slice.id)
+            .into_iter()
+            .flatten()
+            .filter(|tag| !desc_tags.contains(&tag.name))
+            .map(|tag| format!("#{}", tag.name))
+            .collect();
+        for word in missing_tags {
+            desc = format!("{desc} {word}");
+        }
+        if utils::parse_project(&desc).is_none()
+            && let Some(project) = timeline.projects.get(&time_slice.id)
+        {
+            desc = format!("{desc} @{}", project.name);
+        }
+        let desc = desc.trim().to_string();
+        Ok((time_slice, (!desc.is_empty()).then_some(desc)))
+    }
+
+    // 重新打开最近一条记录，返回 false 表示它结束得太久或已开票，需要开始新的计时器
+    fn resume_last(&mut self, within: u64) -> anyhow::Result<bool> {
+        self.sync_open_slice()?;
+        if self.current_timer.as_ref().is_some_and(Clocker::is_running) {
+            return Err(Error::msg("Timer is already running!"));
+        }
+        let (time_slice, _) = self.get_finished_slice(None)?;
+        let ended = time_slice.end_time.unwrap_or_default();
+        if utils::get_current_time().saturating_sub(ended) > within
+            || time_slice.invoice_id.is_some()
+            || !self.db.reopen_time_slice(time_slice.id)?
+        {
+            return Ok(false);
+        }
+        self.sync_open_slice()?;
+        println!("Timer resumed!");
+        Ok(true)
+    }
+
     fn stop_timer(&mut self) -> anyhow::Result<()> {
-        if let Some(timer) = &mut self.current_timer {
-            timer.stop();
-            // Save time slice to database
-            let end_time: u64 = timer
-                .get_end_time()
-                .expect("The timer is stopped, but end time is None");
-
-            let empty_str = "".to_string();
-            let desc_str = self.current_desc.as_ref().unwrap_or(&empty_str);
-            let tags = utils::parse_tags(&desc_str);
-            self.db.insert_time_slice_info(
-                timer.get_start_time(),
-                Some(end_time),
-                &tags,
-                &self.current_desc,
-            )?;
-        } else {
-            println!("No timer is running!");
+        self.sync_open_slice()?;
+        match (&mut self.current_timer, self.current_slice_id) {
+            (Some(timer), Some(time_slice_id)) if timer.is_running() => {
+                timer.stop();
+                let end_time: u64 = timer
+                    .get_end_time()
+                    .expect("The timer is stopped, but end time is None");
+                self.db.finish_time_slice(time_slice_id, end_time)?;
+                self.current_planned_end = None;
+            }
+            _ => println!("No timer is running!"),
         }
         Ok(())
     }
 
     // 获取当前计时器状态
-    fn get_current_timer_status(&self) -> anyhow::Result<TimerStatus> {
+    fn get_current_timer_status(&mut self) -> anyhow::Result<TimerStatus> {
+        self.sync_open_slice()?;
         // 获取当前计时器状态
         self.current_timer
             .as_ref()
-            .and_then(|timer| {
+            .map(|timer| {
                 let start_time = timer.get_start_time();
                 let end_time = timer.get_end_time();
-                Some(TimerStatus {
+                TimerStatus {
                     start_time,
                     end_time,
                     desc: self.current_desc.clone(),
-                })
+                }
             })
             .ok_or(Error::msg("No timer is running!"))
     }
 
+    // 检查提醒时需要的记录状态
+    fn get_activity(&mut self) -> anyhow::Result<Activity> {
+        let running = match self
+            .current_timer
+            .as_ref()
+            .filter(|timer| timer.is_running())
+        {
+            Some(timer) => {
+                let start_time = timer.get_start_time();
+                let desc = self.current_desc.clone();
+                let usual = match &self.usual_cache {
+                    Some(cache) if cache.start_time == start_time && cache.desc == desc => {
+                        cache.usual
+                    }
+                    _ => {
+                        let tags = utils::parse_tags(desc.as_deref().unwrap_or_default());
+                        let usual = usual_duration(&self.get_timeline()?, &tags);
+                        self.usual_cache = Some(UsualDuration {
+                            start_time,
+                            desc: desc.clone(),
+                            usual,
+                        });
+                        usual
+                    }
+                };
+                Some(RunningTimer {
+                    start_time,
+                    desc,
+                    usual,
+                })
+            }
+            None => None,
+        };
+        let last_end = self.db.get_last_end_time()?;
+        Ok(Activity { running, last_end })
+    }
+
+    // 当前计时器的项目与标签对应的预算及其在当前周期的使用情况
+    fn get_running_budgets(&self) -> anyhow::Result<Vec<(Budget, BudgetStatus)>> {
+        let desc = self.current_desc.as_deref().unwrap_or_default();
+        let tags = utils::parse_tags(desc);
+        let project = utils::parse_project(desc);
+        let budgets: Vec<Budget> = self
+            .db
+            .get_all_budgets()?
+            .into_iter()
+            .filter(|budget| budget.matches_names(project.as_deref(), &tags))
+            .collect();
+        if budgets.is_empty() {
+            return Ok(vec![]);
+        }
+        let timeline = self.get_timeline()?;
+        let calendar = calendar();
+        let today = calendar.today();
+        Ok(budgets
+            .into_iter()
+            .map(|budget| {
+                let status = budget.status(&timeline, today, &calendar);
+                (budget, status)
+            })
+            .collect())
+    }
+
     fn get_timeline(&self) -> anyhow::Result<Timeline> {
         let timeslice_list = self.db.get_all_time_slices()?;
         let tags = self.db.get_all_times_tag()?;
@@ -214,10 +997,20 @@ impl App {
                     acc
                 });
 
+        let projects = self.db.get_all_times_project()?;
+        let tag_colors = self
+            .db
+            .get_all_tags()?
+            .into_iter()
+            .filter_map(|tag| Some((tag.name, tag.color?)))
+            .collect();
+
         Ok(Timeline {
             list: timeslice_list,
             tags,
             desc,
+            projects,
+            tag_colors,
         })
     }
 }
@@ -0,0 +1,99 @@
+use super::app::AppHandle;
+use super::config::calendar;
+use super::display::{display_budget_burn_down, display_budgets};
+use super::render::{OutputArgs, OutputFormat};
+use super::utils::parse_duration;
+use crate::core::{
+    budget::{BudgetPeriod, BudgetTarget},
+    timeline::TimelineFilter,
+};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum BudgetCommands {
+    /// Set the time budget of a project or tag
+    Set {
+        /// One of "project" or "tag"
+        target: BudgetTarget,
+        name: String,
+        /// Budgeted time (e.g. "40h")
+        #[arg(value_parser = parse_duration)]
+        amount: u64,
+        /// One of "total", "day", "week" or "month"
+        #[arg(short, long, default_value = "total")]
+        period: BudgetPeriod,
+    },
+    /// List budgets with the time used in the current period
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Remove a budget by its ID
+    #[clap(alias = "rm")]
+    Remove { id: u64 },
+    /// Show the time used and left on each day of the current period
+    BurnDown {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+pub fn handle_budget_command(app_handle: &AppHandle, command: BudgetCommands) {
+    let result = match command {
+        BudgetCommands::Set {
+            target,
+            name,
+            amount,
+            period,
+        } => app_handle
+            .set_budget(target, &name, period, amount)
+            .map(|_| println!("Budget of {} '{name}' set.", target.as_str())),
+        BudgetCommands::List { output } => app_handle.get_budgets().and_then(|budgets| {
+            let timeline = app_handle.get_timeline(&TimelineFilter::default())?;
+            let calendar = calendar();
+            let today = calendar.today();
+            let statuses = budgets
+                .into_iter()
+                .map(|budget| {
+                    let status = budget.status(&timeline, today, &calendar);
+                    (budget, status)
+                })
+                .collect::<Vec<_>>();
+            display_budgets(&statuses, output.format);
+            Ok(())
+        }),
+        BudgetCommands::Remove { id } => app_handle
+            .remove_budget(id)
+            .map(|_| println!("Budget {id} removed.")),
+        BudgetCommands::BurnDown { output } => {
+            show_budget_burn_down(app_handle, output.format);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+// 展示各预算在当前周期内每天的使用与剩余情况
+pub fn show_budget_burn_down(app_handle: &AppHandle, format: OutputFormat) {
+    let result = app_handle.get_budgets().and_then(|budgets| {
+        // 预算按全部记录计算，不受报表筛选条件影响
+        let timeline = app_handle.get_timeline(&TimelineFilter::default())?;
+        let calendar = calendar();
+        let today = calendar.today();
+        let burn_downs = budgets
+            .into_iter()
+            .map(|budget| {
+                let days = budget.burn_down(&timeline, today, &calendar);
+                (budget, days)
+            })
+            .collect::<Vec<_>>();
+        display_budget_burn_down(&burn_downs, format);
+        Ok(())
+    });
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
@@ -0,0 +1,210 @@
+use super::app::AppHandle;
+use super::config::calendar;
+use super::display::{
+    display_daily_chart, display_heatmap, display_sparkline, display_tag_chart, format_duration,
+    terminal_width,
+};
+use super::render::OutputFormat;
+use super::utils::format_date;
+use crate::core::{
+    calendar::Calendar,
+    chart::{ChartDay, HEAT_LEVELS, daily_breakdown, heat_level, weeks},
+    timeline::{Timeline, TimelineFilter},
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::ValueEnum;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ChartKind {
+    /// Horizontal bars of the time per tag
+    Tags,
+    /// Daily bars stacked by top-level tag
+    Daily,
+    /// Sparkline of the daily totals
+    Spark,
+}
+
+impl ChartKind {
+    // 没有指定时间范围时展示最近多少天
+    fn default_days(&self) -> i64 {
+        match self {
+            ChartKind::Spark => 30,
+            _ => 14,
+        }
+    }
+}
+
+// 没有指定时间范围时热力图展示最近一年
+const HEATMAP_DAYS: i64 = 365;
+
+fn get_timeline(app_handle: &AppHandle, filter: &TimelineFilter) -> Option<Timeline> {
+    match app_handle.get_timeline(filter) {
+        Ok(timeline) => Some(timeline),
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    }
+}
+
+// 筛选条件中的日期范围（包含两端），没有指定开始时间时展示截止日期之前的 default_days 天
+fn date_range(
+    filter: &TimelineFilter,
+    default_days: i64,
+    calendar: &Calendar,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let to = filter.end_time.map_or_else(
+        || calendar.today(),
+        |end| calendar.date_of(end.saturating_sub(1)),
+    );
+    let from = filter.start_time.map_or_else(
+        || to - Duration::days(default_days - 1),
+        |start| calendar.date_of(start),
+    );
+    if from > to {
+        println!("The time range is empty.");
+        return None;
+    }
+    Some((from, to))
+}
+
+// width 为 None 时按终端宽度绘制
+pub fn show_chart(
+    app_handle: &AppHandle,
+    kind: ChartKind,
+    filter: &TimelineFilter,
+    width: Option<usize>,
+    format: OutputFormat,
+) {
+    let Some(timeline) = get_timeline(app_handle, filter) else {
+        return;
+    };
+    let width = width.unwrap_or_else(terminal_width);
+    if let ChartKind::Tags = kind {
+        display_tag_chart(&timeline, filter.tags.as_deref(), width, format);
+        return;
+    }
+    let calendar = calendar();
+    let Some((from, to)) = date_range(filter, kind.default_days(), &calendar) else {
+        return;
+    };
+    let days = daily_breakdown(&timeline, from, to, &calendar);
+    match kind {
+        ChartKind::Daily => display_daily_chart(&days, &timeline.tag_colors, width, format),
+        _ => display_sparkline(&days, width, format),
+    }
+}
+
+// svg 不为空时将热力图写入该 SVG 文件，否则在终端中展示
+pub fn show_heatmap(
+    app_handle: &AppHandle,
+    filter: &TimelineFilter,
+    svg: Option<&str>,
+    width: Option<usize>,
+    format: OutputFormat,
+) {
+    let Some(timeline) = get_timeline(app_handle, filter) else {
+        return;
+    };
+    let calendar = calendar();
+    let Some((from, to)) = date_range(filter, HEATMAP_DAYS, &calendar) else {
+        return;
+    };
+    let days = daily_breakdown(&timeline, from, to, &calendar);
+    let Some(path) = svg else {
+        display_heatmap(
+            &days,
+            &calendar,
+            width.unwrap_or_else(terminal_width),
+            format,
+        );
+        return;
+    };
+    match std::fs::write(path, render_svg(&days, &calendar)) {
+        Ok(_) => println!("Heatmap written to {}.", path),
+        Err(e) => println!("{}", e),
+    }
+}
+
+// 与 GitHub 贡献图相同的配色，第一个为没有记录的日期
+const SVG_COLORS: [&str; HEAT_LEVELS + 1] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+// 格子的边长与间距（像素）
+const SVG_CELL: usize = 11;
+const SVG_STEP: usize = 14;
+// 左侧星期与上方月份标签占用的空间
+const SVG_LEFT: usize = 32;
+const SVG_TOP: usize = 20;
+const SVG_TEXT: &str = r##"font-family="sans-serif" font-size="10" fill="#57606a""##;
+
+fn render_svg(days: &[ChartDay], calendar: &Calendar) -> String {
+    let weeks = weeks(days, calendar);
+    let max = days.iter().map(ChartDay::total).max().unwrap_or(0);
+    let width = SVG_LEFT + weeks.len() * SVG_STEP + 8;
+    let height = SVG_TOP + 7 * SVG_STEP + 28;
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    ));
+    out.push_str("  <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n");
+    // 隔行标出星期，与 GitHub 一致
+    for (row, weekday) in calendar
+        .weekdays()
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .step_by(2)
+    {
+        out.push_str(&format!(
+            "  <text x=\"0\" y=\"{}\" {SVG_TEXT}>{weekday}</text>\n",
+            SVG_TOP + row * SVG_STEP + 9
+        ));
+    }
+    let mut last_month = None;
+    for (column, week) in weeks.iter().enumerate() {
+        let x = SVG_LEFT + column * SVG_STEP;
+        // 每月的第一周标出月份
+        if let Some(first) = week.iter().flatten().next()
+            && last_month != Some(first.date.month())
+        {
+            last_month = Some(first.date.month());
+            out.push_str(&format!(
+                "  <text x=\"{x}\" y=\"{}\" {SVG_TEXT}>{}</text>\n",
+                SVG_TOP - 6,
+                first.date.format("%b")
+            ));
+        }
+        for (row, day) in week.iter().enumerate() {
+            let Some(day) = day else {
+                continue;
+            };
+            out.push_str(&format!(
+                "  <rect x=\"{x}\" y=\"{}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" rx=\"2\" fill=\"{}\"><title>{} {}</title></rect>\n",
+                SVG_TOP + row * SVG_STEP,
+                SVG_COLORS[heat_level(day.total(), max)],
+                format_date(day.date),
+                format_duration(day.total())
+            ));
+        }
+    }
+    // 图例放在右下角
+    let legend_y = SVG_TOP + 7 * SVG_STEP + 8;
+    let legend_x = width.saturating_sub(8 + (SVG_COLORS.len() + 4) * SVG_STEP);
+    out.push_str(&format!(
+        "  <text x=\"{}\" y=\"{}\" {SVG_TEXT} text-anchor=\"end\">Less</text>\n",
+        legend_x + 2 * SVG_STEP - 4,
+        legend_y + 9
+    ));
+    for (ind, color) in SVG_COLORS.iter().enumerate() {
+        out.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{legend_y}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" rx=\"2\" fill=\"{color}\"/>\n",
+            legend_x + (ind + 2) * SVG_STEP
+        ));
+    }
+    out.push_str(&format!(
+        "  <text x=\"{}\" y=\"{}\" {SVG_TEXT}>More</text>\n",
+        legend_x + (SVG_COLORS.len() + 2) * SVG_STEP + 2,
+        legend_y + 9
+    ));
+    out.push_str("</svg>\n");
+    out
+}
@@ -1,14 +1,83 @@
 use super::app::AppHandle;
-use super::display::{display_current_timer_status, display_timer_sheet};
-use super::utils::parse_start_args;
-use crate::core::database::Database;
-use clap::{Parser, Subcommand};
+use super::budget::{BudgetCommands, handle_budget_command, show_budget_burn_down};
+use super::chart::{ChartKind, show_chart, show_heatmap};
+use super::completion::ReplHelper;
+use super::config::{self, ConfigCommands, handle_config_command};
+use super::display::{
+    display_current_timer_status, display_earnings, display_tag_tree, display_timer_sheet,
+};
+use super::goal::{GoalCommands, handle_goal_command, show_goal_history, show_goal_progress};
+use super::invoice::{InvoiceCommands, handle_invoice_command};
+use super::picker::pick_recent_description;
+use super::project::{
+    ClientCommands, ProjectCommands, handle_client_command, handle_project_command,
+};
+use super::rate::{RateCommands, handle_rate_command};
+use super::record::{add_record, edit_record};
+use super::render::{OutputArgs, OutputFormat};
+use super::report::show_template_report;
+use super::search::search;
+use super::standup::{StandupArgs, show_standup};
+use super::status::{StatusArgs, show_status};
+use super::tag::{TagCommands, handle_tag_command};
+use super::template::{TemplateCommands, handle_template_command, template_start_args};
+use super::time_parser::parse_time_range;
+use super::utils::{
+    parse_date, parse_date_end, parse_duration, parse_start_args, parse_tag_filter,
+};
+use super::workspace::{
+    Workspace, WorkspaceCommands, data_dir, handle_workspace_command, open_database,
+    read_only_database,
+};
+use crate::core::{
+    calendar::parse_time_zone,
+    rounding::{RoundingMode, RoundingPolicy, RoundingScope},
+    timeline::TimelineFilter,
+};
+use chrono_tz::Tz;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use rustyline::{CompletionType, Editor, error::ReadlineError, history::FileHistory};
+use std::path::{Path, PathBuf};
+
+// 提示符保存的历史命令数
+const HISTORY_SIZE: usize = 1000;
+
+// 启动程序时的命令行参数
+#[derive(Parser)]
+#[command(name = "akashic_log", version, about = "Track time from the terminal")]
+struct AppArgs {
+    /// Database file to use instead of the workspace database [env: AKASHIC_LOG_DB]
+    #[arg(long)]
+    db: Option<PathBuf>,
+    /// Workspace to open instead of the last used one
+    #[arg(short, long)]
+    workspace: Option<String>,
+    /// Run a single command and exit instead of starting the prompt, e.g. status --format "{desc}"
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
 
 #[derive(Parser)]
 #[command(no_binary_name = true)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Time zone used to show and parse times (e.g. "Asia/Tokyo", "UTC", "local")
+    #[arg(long, global = true, value_parser = parse_time_zone)]
+    tz: Option<Tz>,
+}
+
+impl Cli {
+    fn parse_command(args: &[String]) -> Result<Self, clap::Error> {
+        config::set_time_zone_override(None);
+        let cli = Cli::try_parse_from(args)?;
+        // 日期参数在解析时按当前时区换算，指定了 --tz 时需要重新解析
+        if cli.tz.is_some() {
+            config::set_time_zone_override(cli.tz);
+            return Cli::try_parse_from(args);
+        }
+        Ok(cli)
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -22,83 +91,658 @@ enum Commands {
     },
     #[clap(alias = "e")]
     Stop,
+    /// Start a new timer with the description and tags of the last record, or of the given one
+    Continue {
+        /// Id of the record to continue, defaults to the last finished record
+        id: Option<u64>,
+    },
+    /// Reopen the last record if it ended recently, otherwise continue it as a new timer
+    ResumeLast {
+        /// Reopen the record only if it ended at most this long ago (e.g. "15m")
+        #[arg(long, value_parser = parse_duration, default_value = "15m")]
+        within: u64,
+    },
     /// Show current status
     #[clap(alias = "c")]
-    Current,
+    Current {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Print the running timer for shell prompts and status bars
+    Status(StatusArgs),
+    /// Add a finished time record, e.g. add "yesterday 14:00" 1h30m "#code review"
+    Add {
+        /// Start time (e.g. "14:30", "yesterday 9:00", "2 hours ago")
+        start: String,
+        /// End time (e.g. "15:30") or duration (e.g. "1h30m")
+        end: String,
+        /// Description with #tags and @project
+        desc: Vec<String>,
+    },
+    /// Change the time or description of a time record
+    Edit {
+        id: u64,
+        /// New start time, a time of day alone stays on the record's day
+        #[arg(long)]
+        start: Option<String>,
+        /// New end time
+        #[arg(long, conflicts_with = "duration")]
+        end: Option<String>,
+        /// New duration counted from the start time (e.g. "1h30m")
+        #[arg(short, long, value_parser = parse_duration)]
+        duration: Option<u64>,
+        /// New description, replaces the #tags and @project as well
+        #[arg(long)]
+        desc: Option<String>,
+    },
     #[clap(alias = "r")]
     Remove {
         #[arg(required = true)]
         id: u64,
     },
+    /// Show timer history
     #[clap(alias = "l")]
-    List,
+    List {
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Show time per tag as a tree, child tags roll up into their parents
+    Report {
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[command(flatten)]
+        rounding: RoundingArgs,
+        /// Show earnings of billable records using the configured rates
+        #[arg(long)]
+        earnings: bool,
+        /// Currency shown with the earnings, defaults to the configured currency
+        #[arg(long)]
+        currency: Option<String>,
+        /// Decimal places the earnings are rounded to, defaults to the configured precision
+        #[arg(long)]
+        precision: Option<u32>,
+        /// Also show the burn-down of all budgets
+        #[arg(long)]
+        budget: bool,
+        /// Render the report with a template file or the built-in "standup" or "weekly" template
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "round", "round_mode", "round_scope", "earnings", "currency", "precision", "budget",
+                "format",
+            ]
+        )]
+        template: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Summarize the previous working day as a Markdown list for standup notes
+    Standup(StandupArgs),
+    /// Draw bar charts of time per tag, daily stacked bars or a sparkline of daily totals
+    Chart {
+        #[arg(value_enum, default_value_t = ChartKind::Tags)]
+        kind: ChartKind,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Width of the chart in columns, defaults to the terminal width
+        #[arg(short, long)]
+        width: Option<usize>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Show logged time per day as a calendar grid of weeks, or export it as SVG
+    Heatmap {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Write the heatmap to this SVG file instead of printing it
+        #[arg(long, conflicts_with = "format")]
+        svg: Option<String>,
+        /// Width of the heatmap in columns, defaults to the terminal width
+        #[arg(short, long)]
+        width: Option<usize>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Search descriptions, supports "phrases", prefix* and AND/OR/NOT queries
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Maximum number of matches shown
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Mark a time record as billable, or non-billable with --off
+    Billable {
+        id: u64,
+        #[arg(long)]
+        off: bool,
+    },
+    /// Manage hourly rates of projects, clients and tags
+    Rate {
+        #[command(subcommand)]
+        command: RateCommands,
+    },
+    /// Manage daily and weekly goals per tag
+    Goal {
+        #[command(subcommand)]
+        command: GoalCommands,
+    },
+    /// Show streaks and misses of all goals
+    Goals {
+        /// Number of periods to look back
+        #[arg(short, long, default_value_t = 8)]
+        periods: usize,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Manage time budgets of projects and tags
+    Budget {
+        #[command(subcommand)]
+        command: BudgetCommands,
+    },
+    /// Create and manage invoices from billable time
+    Invoice {
+        #[command(subcommand)]
+        command: InvoiceCommands,
+    },
+    /// List tags and set their colors
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+    /// Manage task templates started with "s @name"
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// Manage projects
+    #[clap(alias = "p")]
+    Project {
+        #[command(subcommand)]
+        command: ProjectCommands,
+    },
+    /// Manage clients
+    Client {
+        #[command(subcommand)]
+        command: ClientCommands,
+    },
+    /// Show and change settings stored in the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Switch between workspaces, each one has its own database
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommands,
+    },
     /// Exit the program
     Exit,
 }
 
+#[derive(Args, Debug)]
+struct FilterArgs {
+    /// Only include records within the tag subtree (e.g. "client/acme")
+    #[arg(short, long)]
+    tag: Vec<String>,
+    /// Only include records of the project
+    #[arg(short, long)]
+    project: Vec<String>,
+    /// Only include records of the client's projects
+    #[arg(long)]
+    client: Vec<String>,
+    /// Only include records from this time on (e.g. "2025-02-01", "last monday", "2 hours ago")
+    #[arg(long, value_parser = parse_date)]
+    from: Option<u64>,
+    /// Only include records up to this time, a date includes the whole day (e.g. "yesterday")
+    #[arg(long, value_parser = parse_date_end)]
+    to: Option<u64>,
+    /// Only include records within the range (e.g. "today", "this week", "last month")
+    #[arg(long, value_parser = parse_time_range, conflicts_with_all = ["from", "to"])]
+    range: Option<(u64, u64)>,
+}
+
+#[derive(Args, Debug)]
+pub(super) struct RoundingArgs {
+    /// Round durations to this increment in reports (e.g. "6m", "15m"), "0" turns rounding off
+    #[arg(long, value_parser = parse_duration)]
+    round: Option<u64>,
+    /// Rounding direction: "up", "down" or "nearest"
+    #[arg(long)]
+    round_mode: Option<RoundingMode>,
+    /// Round each "slice", or the total per "day" or per "project"
+    #[arg(long)]
+    round_scope: Option<RoundingScope>,
+}
+
+impl RoundingArgs {
+    // 没有指定的参数使用配置文件中的取整规则
+    pub(super) fn to_policy(&self) -> Option<RoundingPolicy> {
+        let config = config::config();
+        let increment = self
+            .round
+            .or_else(|| config.rounding_policy().map(|policy| policy.increment))
+            .filter(|increment| *increment > 0)?;
+        Some(RoundingPolicy {
+            increment,
+            mode: self.round_mode.unwrap_or(config.rounding.mode),
+            scope: self.round_scope.unwrap_or(config.rounding.scope),
+            day_offset: config.day_offset(),
+        })
+    }
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> TimelineFilter {
+        let names = |names: &[String]| {
+            let names: Vec<String> = names
+                .iter()
+                .map(|name| name.trim_start_matches('@').to_string())
+                .collect();
+            if names.is_empty() { None } else { Some(names) }
+        };
+        TimelineFilter {
+            tags: parse_tag_filter(&self.tag),
+            projects: names(&self.project),
+            clients: names(&self.client),
+            start_time: self.range.map(|(start, _)| start).or(self.from),
+            end_time: self.range.map(|(_, end)| end).or(self.to),
+        }
+    }
+}
+
 pub fn cli_app() -> anyhow::Result<()> {
-    let mut app_handle = AppHandle::new(Database::new("akashic_log.db")?);
-    loop {
-        let input = dialoguer::Input::<String>::new()
-            .with_prompt("")
-            .interact_text()?;
+    let args = AppArgs::parse();
+    let config = config::load()?;
+    let mut workspace = Workspace::resolve(args.db, args.workspace, config.db_path)?;
+    // 提示符与状态栏频繁调用 status，只读取正在运行的计时器，不启动提醒与计时线程
+    if let Ok(Cli {
+        command: Commands::Status(status_args),
+        ..
+    }) = Cli::parse_command(&args.command)
+    {
+        // 数据库还不存在时没有正在运行的计时器
+        let open = if workspace.db_path.exists() {
+            read_only_database(&workspace.db_path).and_then(|db| db.get_open_time_slice())
+        } else {
+            Ok(None)
+        };
+        show_status(open, &status_args);
+        return Ok(());
+    }
+    // 旧版本把数据库放在当前目录下
+    if workspace.name.is_some()
+        && !workspace.db_path.exists()
+        && Path::new("akashic_log.db").exists()
+    {
+        println!(
+            "Found akashic_log.db in the current directory, use --db akashic_log.db to open it."
+        );
+    }
+    let mut app_handle = AppHandle::new(open_database(&workspace.db_path)?);
+    if !args.command.is_empty() {
+        run_command(&mut app_handle, &mut workspace, &args.command);
+        return Ok(());
+    }
+    println!("Database: {}", workspace.db_path.display());
+    run_repl(&mut app_handle, &mut workspace)
+}
 
-        let args = shell_words::split(&input)?;
+// 交互式提示符，历史记录保存在数据目录下，Ctrl-D 退出并保留计时器，连续两次 Ctrl-C 停止计时器并退出
+fn run_repl(app_handle: &mut AppHandle, workspace: &mut Workspace) -> anyhow::Result<()> {
+    let editor_config = rustyline::Config::builder()
+        .max_history_size(HISTORY_SIZE)?
+        .history_ignore_dups(true)?
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<ReplHelper, FileHistory> = Editor::with_config(editor_config)?;
+    // 提醒等后台消息通过 rustyline 输出，输出后重新绘制提示符与正在输入的内容
+    if let Ok(printer) = editor.create_external_printer() {
+        app_handle.set_printer(printer);
+    }
+    let history_path = data_dir().ok().map(|dir| dir.join("history"));
+    if let Some(path) = &history_path
+        && path.exists()
+        && let Err(e) = editor.load_history(path)
+    {
+        println!("Failed to load the command history: {e}");
+    }
+    let cli_command = Cli::command();
+    let mut interrupted = false;
+    loop {
+        // 每次读取输入前重新加载标签与最近的记录，补全时包括刚刚添加的内容
+        editor.set_helper(Some(ReplHelper::new(&cli_command, app_handle)));
+        let input = match editor.readline(": ") {
+            Ok(input) => input,
+            Err(ReadlineError::Interrupted) => {
+                let running = app_handle
+                    .get_current_timer_status()
+                    .is_ok_and(|status| status.end_time.is_none());
+                if interrupted {
+                    if running {
+                        run_command(app_handle, workspace, &["e".to_string()]);
+                    }
+                    println!("Exiting...");
+                    break;
+                }
+                interrupted = true;
+                if running {
+                    println!(
+                        "Press Ctrl-C again to stop the timer and exit, or Ctrl-D to exit and keep it running."
+                    );
+                } else {
+                    println!("Press Ctrl-C again or Ctrl-D to exit.");
+                }
+                continue;
+            }
+            Err(ReadlineError::Eof) => {
+                run_command(app_handle, workspace, &["exit".to_string()]);
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        interrupted = false;
+        if let Some(path) = &history_path
+            && let Err(e) = save_history(&mut editor, path)
+        {
+            println!("Failed to save the command history: {e}");
+        }
 
-        let cli = match Cli::try_parse_from(args) {
-            Ok(cli) => cli,
+        let args = match shell_words::split(&input) {
+            Ok(args) if args.is_empty() => continue,
+            Ok(args) => args,
             Err(e) => {
                 println!("Error: {e}");
-                println!("Available commands:");
-                println!("  s [duration] [#tag description]: start a new timer");
-                println!("  e      : stop current timer");
-                println!("  c      : show current timer");
-                println!("  l      : show timer history");
-                println!("  r [id] : remove time record");
-                println!("  exit");
                 continue;
             }
         };
-        // println!("get command: {:?}", cli.command);
-        // println!("");
-        match cli.command {
-            Commands::Start { args } => {
-                let (duration, desc) = parse_start_args(args);
-                if let Err(e) = app_handle.start_timer(duration, desc) {
-                    println!("{}", e.to_string());
-                    if let Ok(status) = app_handle.get_current_timer_status() {
-                        display_current_timer_status(&status);
+        if !run_command(app_handle, workspace, &args) {
+            break;
+        }
+        println!();
+    }
+    Ok(())
+}
+
+// 每条命令输入后立即追加到历史文件，程序异常退出时也不会丢失
+fn save_history(editor: &mut Editor<ReplHelper, FileHistory>, path: &Path) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    editor.append_history(path)?;
+    Ok(())
+}
+
+// 执行一条命令，返回 false 表示退出程序
+fn run_command(app_handle: &mut AppHandle, workspace: &mut Workspace, args: &[String]) -> bool {
+    let cli = match Cli::parse_command(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            println!("Error: {e}");
+            println!("Available commands:");
+            println!(
+                "  s [duration|pomodoro|break|long-break|@template] [#tag @project description]: start a new timer"
+            );
+            println!("  s      : pick a recent description to start a timer with");
+            println!("  e      : stop current timer");
+            println!("  c      : show current timer");
+            println!(
+                "  continue [id] : start a new timer with the description of the last (or given) record"
+            );
+            println!(
+                "  resume-last [--within 15m] : reopen the last record if it ended recently, otherwise continue it"
+            );
+            println!(
+                "  status [--format \"{{elapsed}} {{desc}}\"] [--waybar] : print the running timer for prompts and status bars"
+            );
+            println!("  add <start> <end|duration> [description] : add a finished time record");
+            println!(
+                "  edit <id> [--start time] [--end time] [--duration d] [--desc text] : edit a time record"
+            );
+            println!(
+                "  l [--tag tag] [--project p] [--client c] [--from time] [--to time] [--range \"this week\"] : show timer history"
+            );
+            println!(
+                "  --format <table|json|csv|markdown|html> : output format of l, c, report, standup, chart, heatmap, goals, search and the list commands"
+            );
+            println!(
+                "  report [--tag tag] [--earnings] [--round 15m] [--budget] [--template standup|weekly|file] : show time per tag tree, earnings or a templated report"
+            );
+            println!(
+                "  standup [--date date] [--tag tag] : summarize the previous working day for standup notes"
+            );
+            println!(
+                "  chart [tags|daily|spark] [--tag tag] [--range \"last month\"] [--width n] : draw time charts"
+            );
+            println!(
+                "  heatmap [--tag tag] [--range \"this year\"] [--svg file] : show time per day as a calendar grid"
+            );
+            println!(
+                "  search <query> [--tag tag] [--from date] [--to date] : search descriptions"
+            );
+            println!("  billable [id] [--off] : mark a time record (non-)billable");
+            println!("  rate <set|list|remove>");
+            println!("  invoice <create|list|void>");
+            println!("  goal <add|list|remove|progress>");
+            println!("  goals [--periods n] : show goal streaks and misses");
+            println!("  budget <set|list|remove|burn-down>");
+            println!("  r [id] : remove time record");
+            println!("  tag <list|color>");
+            println!(
+                "  template <add|list|remove> : task templates, start one with s @name or pick with s @"
+            );
+            println!("  project <add|list|rename|client|archive|unarchive|remove|totals>");
+            println!("  client <add|list|rename|archive|unarchive|remove>");
+            println!("  config <get|set|unset|list|path>");
+            println!("  workspace <use|list|current>");
+            println!("  --tz <zone> : show and parse times in another time zone, e.g. l --tz UTC");
+            println!(
+                "  exit   : exit and keep the timer running, same as Ctrl-D; Ctrl-C twice stops it first"
+            );
+            return true;
+        }
+    };
+    // println!("get command: {:?}", cli.command);
+    // println!("");
+    match cli.command {
+        Commands::Start { args } => {
+            let (duration, desc) = match template_start_args(app_handle, &args) {
+                Ok(Some(start)) => start,
+                Ok(None) if args.is_empty() => match pick_recent_description(app_handle) {
+                    Ok(desc) => (None, desc),
+                    Err(e) => {
+                        println!("{}", e);
+                        return true;
                     }
+                },
+                Ok(None) => match parse_start_args(args) {
+                    Ok(start) => start,
+                    Err(e) => {
+                        println!("{}", e);
+                        return true;
+                    }
+                },
+                Err(e) => {
+                    println!("{}", e);
+                    return true;
                 }
-            }
-            Commands::Stop => {
-                if let Ok(status) = app_handle.stop_timer() {
-                    display_current_timer_status(&status);
-                }
-            }
-            Commands::Current => {
+            };
+            if let Err(e) = app_handle.start_timer(duration, desc) {
+                println!("{}", e);
                 if let Ok(status) = app_handle.get_current_timer_status() {
-                    display_current_timer_status(&status);
+                    display_current_timer_status(&status, OutputFormat::Table);
                 }
             }
-            Commands::Remove { id } => {
-                if let Ok(()) = app_handle.remove_time_slice(id) {
-                    if let Ok(timeline) = app_handle.get_timeline(None, None, None) {
-                        display_timer_sheet(&timeline);
+        }
+        Commands::Stop => {
+            if let Ok(status) = app_handle.stop_timer() {
+                display_current_timer_status(&status, OutputFormat::Table);
+            }
+        }
+        Commands::Continue { id } => match app_handle.continue_timer(id) {
+            Ok(status) => display_current_timer_status(&status, OutputFormat::Table),
+            Err(e) => println!("{}", e),
+        },
+        Commands::ResumeLast { within } => match app_handle.resume_last(within) {
+            Ok(status) => display_current_timer_status(&status, OutputFormat::Table),
+            Err(e) => println!("{}", e),
+        },
+        Commands::Status(args) => show_status(app_handle.get_open_time_slice(), &args),
+        Commands::Current { output } => {
+            if let Ok(status) = app_handle.get_current_timer_status() {
+                display_current_timer_status(&status, output.format);
+            }
+            if output.format.is_table() {
+                show_goal_progress(app_handle, OutputFormat::Table);
+            }
+        }
+        Commands::Add { start, end, desc } => {
+            if let Err(e) = add_record(app_handle, &start, &end, desc) {
+                println!("{}", e);
+            }
+        }
+        Commands::Edit {
+            id,
+            start,
+            end,
+            duration,
+            desc,
+        } => {
+            if let Err(e) = edit_record(app_handle, id, start, end, duration, desc) {
+                println!("{}", e);
+            }
+        }
+        Commands::Remove { id } => {
+            if let Ok(()) = app_handle.remove_time_slice(id)
+                && let Ok(timeline) = app_handle.get_timeline(&TimelineFilter::default())
+            {
+                display_timer_sheet(&timeline, OutputFormat::Table);
+            }
+        }
+        Commands::List { filter, output } => {
+            if let Ok(timeline) = app_handle.get_timeline(&filter.to_filter()) {
+                display_timer_sheet(&timeline, output.format);
+            }
+            if output.format.is_table() {
+                show_goal_progress(app_handle, OutputFormat::Table);
+            }
+        }
+        Commands::Report {
+            filter,
+            template: Some(template),
+            ..
+        } => show_template_report(app_handle, &filter.to_filter(), &template),
+        // 燃尽图与报表是两张表，其它格式只能输出一张
+        Commands::Report {
+            budget: true,
+            output,
+            ..
+        } if !output.format.is_table() => println!(
+            "--budget only works with the table format, use \"budget burn-down --format ...\" instead."
+        ),
+        Commands::Report {
+            filter,
+            rounding,
+            earnings,
+            currency,
+            precision,
+            budget,
+            template: None,
+            output,
+        } => {
+            let filter = filter.to_filter();
+            let rounding = rounding.to_policy();
+            if let Ok(timeline) = app_handle.get_timeline(&filter) {
+                if earnings {
+                    match app_handle.get_rate_book() {
+                        Ok(rate_book) => display_earnings(
+                            &rate_book.earnings(&timeline, rounding.as_ref()),
+                            &currency.unwrap_or_else(|| config::config().currency),
+                            precision.unwrap_or_else(|| config::config().precision),
+                            output.format,
+                        ),
+                        Err(e) => println!("{}", e),
                     }
+                } else {
+                    display_tag_tree(
+                        &timeline,
+                        filter.tags.as_deref(),
+                        rounding.as_ref(),
+                        output.format,
+                    );
                 }
             }
-            Commands::List => {
-                if let Ok(timeline) = app_handle.get_timeline(None, None, None) {
-                    display_timer_sheet(&timeline);
-                }
+            if budget {
+                show_budget_burn_down(app_handle, OutputFormat::Table);
             }
-            Commands::Exit => {
-                println!("Exiting...");
-                break;
+        }
+        Commands::Standup(args) => show_standup(app_handle, &args),
+        Commands::Chart {
+            kind,
+            filter,
+            width,
+            output,
+        } => show_chart(app_handle, kind, &filter.to_filter(), width, output.format),
+        Commands::Heatmap {
+            filter,
+            svg,
+            width,
+            output,
+        } => show_heatmap(
+            app_handle,
+            &filter.to_filter(),
+            svg.as_deref(),
+            width,
+            output.format,
+        ),
+        Commands::Search {
+            query,
+            filter,
+            limit,
+            output,
+        } => search(
+            app_handle,
+            &query.join(" "),
+            &filter.to_filter(),
+            limit,
+            output.format,
+        ),
+        Commands::Billable { id, off } => match app_handle.set_billable(id, !off) {
+            Ok(()) if off => println!("Time record {id} marked as non-billable."),
+            Ok(()) => println!("Time record {id} marked as billable."),
+            Err(e) => println!("{}", e),
+        },
+        Commands::Rate { command } => handle_rate_command(app_handle, command),
+        Commands::Invoice { command } => handle_invoice_command(app_handle, command),
+        Commands::Goal { command } => handle_goal_command(app_handle, command),
+        Commands::Goals { periods, output } => {
+            show_goal_history(app_handle, periods, output.format)
+        }
+        Commands::Budget { command } => handle_budget_command(app_handle, command),
+        Commands::Tag { command } => handle_tag_command(app_handle, command),
+        Commands::Template { command } => handle_template_command(app_handle, command),
+        Commands::Project { command } => handle_project_command(app_handle, command),
+        Commands::Client { command } => handle_client_command(app_handle, command),
+        Commands::Config { command } => handle_config_command(command),
+        Commands::Workspace { command } => handle_workspace_command(app_handle, workspace, command),
+        Commands::Exit => {
+            // 计时器保存在数据库中，退出后继续计时
+            if app_handle
+                .get_current_timer_status()
+                .is_ok_and(|status| status.end_time.is_none())
+            {
+                println!("The timer keeps running, stop it later with \"e\".");
             }
+            println!("Exiting...");
+            return false;
         }
-        println!("");
     }
-    Ok(())
+    true
 }
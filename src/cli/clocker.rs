@@ -24,6 +24,15 @@ impl Clocker {
         }
     }
 
+    // 恢复一个已经开始的计时器，例如由其它进程启动并写入数据库的计时器
+    pub fn resume(start_time: u64) -> Self {
+        Clocker {
+            start_time: Some(start_time),
+            end_time: None,
+            status: ClockerStatus::Running,
+        }
+    }
+
     pub fn start(&mut self) {
         self.start_time = Some(Self::get_current_timestamp());
         self.status = ClockerStatus::Running;
@@ -35,6 +44,7 @@ impl Clocker {
         self.status = ClockerStatus::Stopped;
     }
 
+    #[allow(dead_code, private_interfaces)]
     pub fn get_status(&self) -> &ClockerStatus {
         &self.status
     }
@@ -47,6 +57,7 @@ impl Clocker {
         self.end_time
     }
 
+    #[allow(dead_code)]
     pub fn get_duration(&self) -> u64 {
         if !self.is_running() {
             self.get_end_time()
@@ -57,6 +68,7 @@ impl Clocker {
         }
     }
 
+    #[allow(clippy::needless_bool)]
     pub fn is_running(&self) -> bool {
         if self.status == ClockerStatus::Running {
             true
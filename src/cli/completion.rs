@@ -0,0 +1,120 @@
+use super::app::AppHandle;
+use rustyline::{
+    Context, Helper,
+    completion::{Completer, Pair},
+    highlight::Highlighter,
+    hint::Hinter,
+    validate::Validator,
+};
+
+// 补全记录 ID 时列出的最近记录数
+const RECENT_RECORDS: usize = 20;
+
+// 提示符中按 Tab 补全第一个单词的命令名、以 # 开头的标签，以及 r、edit 等命令的记录 ID
+pub struct ReplHelper {
+    commands: Vec<String>,
+    // 第一个参数为记录 ID 的命令
+    id_commands: Vec<String>,
+    tags: Vec<String>,
+    records: Vec<(u64, Option<String>)>,
+}
+
+impl ReplHelper {
+    pub fn new(command: &clap::Command, app_handle: &AppHandle) -> Self {
+        let names = |command: &clap::Command| -> Vec<String> {
+            std::iter::once(command.get_name())
+                .chain(command.get_all_aliases())
+                .map(str::to_string)
+                .collect()
+        };
+        let mut commands: Vec<String> = command.get_subcommands().flat_map(names).collect();
+        commands.sort();
+        let id_commands = command
+            .get_subcommands()
+            .filter(|command| {
+                command
+                    .get_positionals()
+                    .next()
+                    .is_some_and(|arg| arg.get_id() == "id")
+            })
+            .flat_map(names)
+            .collect();
+        let mut tags: Vec<String> = app_handle
+            .get_tags()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tag| format!("#{}", tag.name))
+            .collect();
+        tags.sort();
+        ReplHelper {
+            commands,
+            id_commands,
+            tags,
+            records: app_handle
+                .get_recent_time_slice_ids(RECENT_RECORDS)
+                .unwrap_or_default(),
+        }
+    }
+
+    // 光标前正在输入的单词的开始位置，以及可以补全的候选项
+    fn candidates(&self, line: &str) -> (usize, Vec<Pair>) {
+        let word = line.rsplit(char::is_whitespace).next().unwrap_or_default();
+        let start = line.len() - word.len();
+        let previous: Vec<&str> = line[..start].split_whitespace().collect();
+        let pair = |name: &String| Pair {
+            display: name.clone(),
+            replacement: name.clone(),
+        };
+        let candidates = match previous.as_slice() {
+            [] => self
+                .commands
+                .iter()
+                .filter(|name| name.starts_with(word))
+                .map(pair)
+                .collect(),
+            _ if word.starts_with('#') => self
+                .tags
+                .iter()
+                .filter(|tag| tag.starts_with(word))
+                .map(pair)
+                .collect(),
+            [command] if self.id_commands.iter().any(|name| name == command) => self
+                .records
+                .iter()
+                .map(|(id, desc)| (id.to_string(), desc))
+                .filter(|(id, _)| id.starts_with(word))
+                .map(|(id, desc)| Pair {
+                    display: format!("{id}  {}", desc.as_deref().unwrap_or_default().trim())
+                        .trim_end()
+                        .to_string(),
+                    replacement: id,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        (start, candidates)
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
@@ -0,0 +1,716 @@
+use super::utils::{check_time_format, parse_duration};
+use crate::core::calendar::{Calendar, parse_time_zone, system_time_zone};
+use crate::core::reminder::{ReminderSettings, WorkingHours};
+use crate::core::rounding::{RoundingMode, RoundingPolicy, RoundingScope};
+use anyhow::{Context, anyhow};
+use chrono::{NaiveTime, Timelike, Weekday};
+use chrono_tz::Tz;
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Show the value of a setting, e.g. "format.date"
+    Get { key: String },
+    /// Change a setting and save it to the config file
+    Set { key: String, value: String },
+    /// Remove a setting, it falls back to the default value
+    Unset { key: String },
+    /// List all settings
+    #[clap(alias = "ls")]
+    List,
+    /// Show the path of the config file
+    Path,
+}
+
+// 用户配置，保存在 ~/.config/akashic_log/config.toml，未设置的项使用默认值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // 数据库文件，未设置时使用工作区的数据库
+    pub db_path: Option<PathBuf>,
+    // 时区，例如 "Asia/Shanghai"，未设置时使用系统时区
+    pub timezone: Option<String>,
+    pub currency: String,
+    // 金额保留的小数位数
+    pub precision: u32,
+    pub format: FormatConfig,
+    pub week: WeekConfig,
+    pub day: DayConfig,
+    pub pomodoro: PomodoroConfig,
+    pub rounding: RoundingConfig,
+    pub colors: ColorConfig,
+    pub reminders: ReminderConfig,
+    pub working_hours: WorkingHoursConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatConfig {
+    // chrono 的格式字符串
+    pub date: String,
+    pub time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeekConfig {
+    // 每周的第一天，例如 "monday" 或 "sunday"
+    pub start: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayConfig {
+    // 每天开始的时刻，例如 "04:00" 时凌晨四点前的记录算作前一天
+    pub start: String,
+    // 跨越每天开始时刻的记录是否在统计中按时长拆分到两天
+    pub split: bool,
+}
+
+// 番茄钟时长，例如 "25m"，可以通过 s pomodoro、s break、s long-break 使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    pub work: String,
+    pub short_break: String,
+    pub long_break: String,
+}
+
+// 报表默认的取整规则，命令行参数优先
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundingConfig {
+    pub increment: Option<String>,
+    pub mode: RoundingMode,
+    pub scope: RoundingScope,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorConfig {
+    pub enabled: bool,
+    // 搜索结果中匹配词的颜色
+    pub highlight: String,
+    // 标签的颜色，子标签没有设置时使用父标签的颜色
+    pub tags: BTreeMap<String, String>,
+}
+
+// 提醒设置，空闲提醒只在工作时间内出现
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReminderConfig {
+    pub enabled: bool,
+    // 没有计时器运行多久后提醒，例如 "15m"
+    pub idle: String,
+    // 计时器运行时长超过该标签通常时长的多少倍时提醒
+    pub long_running: f64,
+    // 同一提醒再次出现的间隔
+    pub repeat: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkingHoursConfig {
+    // 工作日，例如 "mon-fri" 或 "mon,wed,fri"
+    pub days: String,
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            db_path: None,
+            timezone: None,
+            currency: "USD".to_string(),
+            precision: 2,
+            format: FormatConfig::default(),
+            week: WeekConfig::default(),
+            day: DayConfig::default(),
+            pomodoro: PomodoroConfig::default(),
+            rounding: RoundingConfig::default(),
+            colors: ColorConfig::default(),
+            reminders: ReminderConfig::default(),
+            working_hours: WorkingHoursConfig::default(),
+        }
+    }
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            date: "%Y-%m-%d".to_string(),
+            time: "%H:%M:%S".to_string(),
+        }
+    }
+}
+
+impl Default for WeekConfig {
+    fn default() -> Self {
+        WeekConfig {
+            start: "monday".to_string(),
+        }
+    }
+}
+
+impl Default for DayConfig {
+    fn default() -> Self {
+        DayConfig {
+            start: "00:00".to_string(),
+            split: false,
+        }
+    }
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        PomodoroConfig {
+            work: "25m".to_string(),
+            short_break: "5m".to_string(),
+            long_break: "15m".to_string(),
+        }
+    }
+}
+
+impl Default for RoundingConfig {
+    fn default() -> Self {
+        RoundingConfig {
+            increment: None,
+            mode: RoundingMode::Nearest,
+            scope: RoundingScope::Slice,
+        }
+    }
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        ColorConfig {
+            enabled: true,
+            highlight: "yellow".to_string(),
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        ReminderConfig {
+            enabled: true,
+            idle: "15m".to_string(),
+            long_running: 2.0,
+            repeat: "30m".to_string(),
+        }
+    }
+}
+
+impl Default for WorkingHoursConfig {
+    fn default() -> Self {
+        WorkingHoursConfig {
+            days: "mon-fri".to_string(),
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+        }
+    }
+}
+
+// 支持的颜色名称与对应的终端颜色代码
+const COLORS: [(&str, &str); 8] = [
+    ("black", "30"),
+    ("red", "31"),
+    ("green", "32"),
+    ("yellow", "33"),
+    ("blue", "34"),
+    ("magenta", "35"),
+    ("cyan", "36"),
+    ("white", "37"),
+];
+
+pub fn color_code(name: &str) -> Option<&'static str> {
+    COLORS
+        .iter()
+        .find(|(color, _)| *color == name)
+        .map(|(_, code)| *code)
+}
+
+// 解析命令行中的颜色名称
+pub fn parse_color(name: &str) -> Result<String, String> {
+    match color_code(name) {
+        Some(_) => Ok(name.to_string()),
+        None => Err(format!(
+            "Invalid color: '{name}'. Expected one of {}",
+            COLORS.map(|(name, _)| name).join(", ")
+        )),
+    }
+}
+
+const KEYS: [&str; 24] = [
+    "db_path",
+    "timezone",
+    "currency",
+    "precision",
+    "format.date",
+    "format.time",
+    "week.start",
+    "day.start",
+    "day.split",
+    "pomodoro.work",
+    "pomodoro.short_break",
+    "pomodoro.long_break",
+    "rounding.increment",
+    "rounding.mode",
+    "rounding.scope",
+    "colors.enabled",
+    "colors.highlight",
+    "reminders.enabled",
+    "reminders.idle",
+    "reminders.long_running",
+    "reminders.repeat",
+    "working_hours.days",
+    "working_hours.start",
+    "working_hours.end",
+];
+
+impl Config {
+    pub fn week_start(&self) -> Weekday {
+        self.week.start.parse().unwrap_or(Weekday::Mon)
+    }
+
+    // 每天开始的时刻距零点的毫秒数
+    pub fn day_offset(&self) -> u64 {
+        parse_day_start(&self.day.start).unwrap_or(0)
+    }
+
+    // 工作日，设置无效时为周一到周五
+    pub fn working_days(&self) -> Vec<Weekday> {
+        parse_weekdays(&self.working_hours.days).unwrap_or_else(|_| {
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]
+        })
+    }
+
+    // 提醒的设置，关闭提醒或设置无效时返回 None
+    pub fn reminder_settings(&self) -> Option<ReminderSettings> {
+        if !self.reminders.enabled {
+            return None;
+        }
+        Some(ReminderSettings {
+            idle_after: parse_duration(&self.reminders.idle).ok()?,
+            long_running_factor: self.reminders.long_running,
+            repeat: parse_duration(&self.reminders.repeat).ok()?,
+            working_hours: WorkingHours {
+                days: parse_weekdays(&self.working_hours.days).ok()?,
+                start: parse_time_of_day("working_hours.start", &self.working_hours.start).ok()?,
+                end: parse_time_of_day("working_hours.end", &self.working_hours.end).ok()?,
+            },
+        })
+    }
+
+    pub fn time_zone(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|name| parse_time_zone(name).ok())
+            .unwrap_or_else(system_time_zone)
+    }
+
+    // 报表默认的取整规则，没有设置取整单位时不取整
+    pub fn rounding_policy(&self) -> Option<RoundingPolicy> {
+        let increment = parse_duration(self.rounding.increment.as_deref()?).ok()?;
+        Some(RoundingPolicy {
+            increment,
+            mode: self.rounding.mode,
+            scope: self.rounding.scope,
+            day_offset: self.day_offset(),
+        })
+    }
+
+    // 标签的颜色代码，tag_colors 为 tag color 设置在标签上的颜色，优先于配置文件
+    // 子标签继承最近的设置了颜色的祖先标签
+    pub fn tag_color(
+        &self,
+        tag: &str,
+        tag_colors: &HashMap<String, String>,
+    ) -> Option<&'static str> {
+        if !self.colors.enabled {
+            return None;
+        }
+        let mut path = tag;
+        loop {
+            if let Some(color) = tag_colors.get(path).or(self.colors.tags.get(path)) {
+                return color_code(color);
+            }
+            path = path.rsplit_once('/')?.0;
+        }
+    }
+
+    pub fn highlight_color(&self) -> Option<&'static str> {
+        if !self.colors.enabled {
+            return None;
+        }
+        color_code(&self.colors.highlight)
+    }
+
+    // 检查配置项的取值是否合法
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(timezone) = &self.timezone {
+            parse_time_zone(timezone).map_err(anyhow::Error::msg)?;
+        }
+        self.week
+            .start
+            .parse::<Weekday>()
+            .map_err(|_| anyhow!("Invalid week.start: '{}'", self.week.start))?;
+        parse_day_start(&self.day.start)?;
+        for (key, format) in [
+            ("format.date", &self.format.date),
+            ("format.time", &self.format.time),
+        ] {
+            check_time_format(format).map_err(|e| anyhow!("Invalid {key}: {e}"))?;
+        }
+        parse_weekdays(&self.working_hours.days)?;
+        parse_time_of_day("working_hours.start", &self.working_hours.start)?;
+        parse_time_of_day("working_hours.end", &self.working_hours.end)?;
+        if self.reminders.long_running <= 0.0 {
+            return Err(anyhow!(
+                "Invalid reminders.long_running: '{}'. Expected a positive number",
+                self.reminders.long_running
+            ));
+        }
+        for (key, value) in [
+            ("pomodoro.work", &self.pomodoro.work),
+            ("pomodoro.short_break", &self.pomodoro.short_break),
+            ("pomodoro.long_break", &self.pomodoro.long_break),
+            ("reminders.idle", &self.reminders.idle),
+            ("reminders.repeat", &self.reminders.repeat),
+        ] {
+            parse_duration(value).map_err(|e| anyhow!("Invalid {key}: {e}"))?;
+        }
+        if let Some(increment) = &self.rounding.increment {
+            parse_duration(increment).map_err(|e| anyhow!("Invalid rounding.increment: {e}"))?;
+        }
+        for (key, color) in [("colors.highlight", &self.colors.highlight)]
+            .into_iter()
+            .chain(
+                self.colors
+                    .tags
+                    .iter()
+                    .map(|(tag, color)| (tag.as_str(), color)),
+            )
+        {
+            if color_code(color).is_none() {
+                return Err(anyhow!(
+                    "Invalid color for {key}: '{color}'. Expected one of {}",
+                    COLORS.map(|(name, _)| name).join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let value = match key {
+            "db_path" => self.db_path.as_ref().map(|path| path.display().to_string()),
+            "timezone" => self.timezone.clone(),
+            "currency" => Some(self.currency.clone()),
+            "precision" => Some(self.precision.to_string()),
+            "format.date" => Some(self.format.date.clone()),
+            "format.time" => Some(self.format.time.clone()),
+            "week.start" => Some(self.week.start.clone()),
+            "day.start" => Some(self.day.start.clone()),
+            "day.split" => Some(self.day.split.to_string()),
+            "pomodoro.work" => Some(self.pomodoro.work.clone()),
+            "pomodoro.short_break" => Some(self.pomodoro.short_break.clone()),
+            "pomodoro.long_break" => Some(self.pomodoro.long_break.clone()),
+            "rounding.increment" => self.rounding.increment.clone(),
+            "rounding.mode" => Some(format!("{:?}", self.rounding.mode).to_lowercase()),
+            "rounding.scope" => Some(format!("{:?}", self.rounding.scope).to_lowercase()),
+            "colors.enabled" => Some(self.colors.enabled.to_string()),
+            "colors.highlight" => Some(self.colors.highlight.clone()),
+            "reminders.enabled" => Some(self.reminders.enabled.to_string()),
+            "reminders.idle" => Some(self.reminders.idle.clone()),
+            "reminders.long_running" => Some(self.reminders.long_running.to_string()),
+            "reminders.repeat" => Some(self.reminders.repeat.clone()),
+            "working_hours.days" => Some(self.working_hours.days.clone()),
+            "working_hours.start" => Some(self.working_hours.start.clone()),
+            "working_hours.end" => Some(self.working_hours.end.clone()),
+            _ => match key.strip_prefix("colors.tags.") {
+                Some(tag) => self.colors.tags.get(tag).cloned(),
+                None => return Err(unknown_key(key)),
+            },
+        };
+        Ok(value)
+    }
+
+    // value 为 None 时恢复默认值
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> anyhow::Result<()> {
+        let default = Config::default();
+        let string = |default: String| value.map_or(default, str::to_string);
+        match key {
+            "db_path" => self.db_path = value.map(PathBuf::from),
+            "timezone" => self.timezone = value.map(str::to_string),
+            "currency" => self.currency = string(default.currency),
+            "precision" => {
+                self.precision = match value {
+                    Some(value) => value
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid precision: '{value}'"))?,
+                    None => default.precision,
+                }
+            }
+            "format.date" => self.format.date = string(default.format.date),
+            "format.time" => self.format.time = string(default.format.time),
+            "week.start" => self.week.start = string(default.week.start).to_lowercase(),
+            "day.start" => self.day.start = string(default.day.start),
+            "day.split" => {
+                self.day.split = match value {
+                    Some(value) => value
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid day.split: '{value}'"))?,
+                    None => default.day.split,
+                }
+            }
+            "pomodoro.work" => self.pomodoro.work = string(default.pomodoro.work),
+            "pomodoro.short_break" => {
+                self.pomodoro.short_break = string(default.pomodoro.short_break)
+            }
+            "pomodoro.long_break" => self.pomodoro.long_break = string(default.pomodoro.long_break),
+            "rounding.increment" => self.rounding.increment = value.map(str::to_string),
+            "rounding.mode" => {
+                self.rounding.mode = match value {
+                    Some(value) => value.parse().map_err(anyhow::Error::msg)?,
+                    None => default.rounding.mode,
+                }
+            }
+            "rounding.scope" => {
+                self.rounding.scope = match value {
+                    Some(value) => value.parse().map_err(anyhow::Error::msg)?,
+                    None => default.rounding.scope,
+                }
+            }
+            "colors.enabled" => {
+                self.colors.enabled = match value {
+                    Some(value) => value
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid colors.enabled: '{value}'"))?,
+                    None => default.colors.enabled,
+                }
+            }
+            "colors.highlight" => self.colors.highlight = string(default.colors.highlight),
+            "reminders.enabled" => {
+                self.reminders.enabled = match value {
+                    Some(value) => value
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid reminders.enabled: '{value}'"))?,
+                    None => default.reminders.enabled,
+                }
+            }
+            "reminders.idle" => self.reminders.idle = string(default.reminders.idle),
+            "reminders.long_running" => {
+                self.reminders.long_running = match value {
+                    Some(value) => value
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid reminders.long_running: '{value}'"))?,
+                    None => default.reminders.long_running,
+                }
+            }
+            "reminders.repeat" => self.reminders.repeat = string(default.reminders.repeat),
+            "working_hours.days" => {
+                self.working_hours.days = string(default.working_hours.days).to_lowercase()
+            }
+            "working_hours.start" => self.working_hours.start = string(default.working_hours.start),
+            "working_hours.end" => self.working_hours.end = string(default.working_hours.end),
+            _ => match key.strip_prefix("colors.tags.") {
+                Some(tag) => match value {
+                    Some(color) => {
+                        self.colors.tags.insert(tag.to_string(), color.to_string());
+                    }
+                    None => {
+                        self.colors.tags.remove(tag);
+                    }
+                },
+                None => return Err(unknown_key(key)),
+            },
+        }
+        self.validate()
+    }
+
+    // 所有配置项及其取值，未设置的项为 None
+    pub fn entries(&self) -> Vec<(String, Option<String>)> {
+        KEYS.iter()
+            .map(|key| (key.to_string(), self.get(key).unwrap_or_default()))
+            .chain(
+                self.colors
+                    .tags
+                    .iter()
+                    .map(|(tag, color)| (format!("colors.tags.{tag}"), Some(color.clone()))),
+            )
+            .collect()
+    }
+}
+
+// 解析每天开始的时刻，例如 "04:00"，返回距零点的毫秒数
+fn parse_day_start(s: &str) -> anyhow::Result<u64> {
+    let time = parse_time_of_day("day.start", s)?;
+    Ok(time.num_seconds_from_midnight() as u64 * 1000)
+}
+
+fn parse_time_of_day(key: &str, s: &str) -> anyhow::Result<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .map_err(|_| anyhow!("Invalid {key}: '{s}'. Expected a time like \"09:00\""))
+}
+
+// 解析工作日，支持逗号分隔与范围，例如 "mon-fri"、"mon,wed,fri"、"sat-mon"
+fn parse_weekdays(s: &str) -> anyhow::Result<Vec<Weekday>> {
+    let invalid = || anyhow!("Invalid working_hours.days: '{s}'. Expected e.g. \"mon-fri\"");
+    let mut days = vec![];
+    for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let mut day = first.trim().parse::<Weekday>().map_err(|_| invalid())?;
+                let last = last.trim().parse::<Weekday>().map_err(|_| invalid())?;
+                days.push(day);
+                while day != last {
+                    day = day.succ();
+                    days.push(day);
+                }
+            }
+            None => days.push(part.parse::<Weekday>().map_err(|_| invalid())?),
+        }
+    }
+    if days.is_empty() {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown setting: '{key}'. Available: {}, colors.tags.<tag>",
+        KEYS.join(", ")
+    )
+}
+
+// 配置文件路径，遵循 XDG 规范，例如 ~/.config/akashic_log/config.toml
+pub fn config_path() -> anyhow::Result<PathBuf> {
+    dirs::config_dir()
+        .map(|dir| dir.join("akashic_log").join("config.toml"))
+        .ok_or_else(|| anyhow!("Can't find the config directory"))
+}
+
+// 读取配置文件，文件不存在时使用默认配置
+pub fn load() -> anyhow::Result<Config> {
+    let path = config_path()?;
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => {
+            let config: Config = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            config
+                .validate()
+                .with_context(|| format!("Invalid config in {}", path.display()))?;
+            config
+        }
+        Err(_) => Config::default(),
+    };
+    *CONFIG.write().expect("Get config lock failed") = config.clone();
+    Ok(config)
+}
+
+fn save(config: &Config) -> anyhow::Result<()> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, toml::to_string_pretty(config)?)?;
+    *CONFIG.write().expect("Get config lock failed") = config.clone();
+    Ok(())
+}
+
+// 当前生效的配置，程序启动时由 load 读取
+static CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| RwLock::new(Config::default()));
+
+pub fn config() -> Config {
+    CONFIG.read().expect("Get config lock failed").clone()
+}
+
+// 读取当前配置中的部分内容，逐行格式化时无需复制整个配置
+pub fn with_config<T>(f: impl FnOnce(&Config) -> T) -> T {
+    f(&CONFIG.read().expect("Get config lock failed"))
+}
+
+// 当前命令通过 --tz 指定的时区，优先于配置文件
+static TIME_ZONE_OVERRIDE: RwLock<Option<Tz>> = RwLock::new(None);
+
+pub fn set_time_zone_override(time_zone: Option<Tz>) {
+    *TIME_ZONE_OVERRIDE
+        .write()
+        .expect("Get time zone lock failed") = time_zone;
+}
+
+// 展示与解析时间使用的时区：--tz 参数 > 配置文件中的 timezone > 系统时区
+pub fn time_zone() -> Tz {
+    TIME_ZONE_OVERRIDE
+        .read()
+        .expect("Get time zone lock failed")
+        .unwrap_or_else(|| with_config(Config::time_zone))
+}
+
+pub fn calendar() -> Calendar {
+    let config = config();
+    Calendar {
+        time_zone: time_zone(),
+        week_start: config.week_start(),
+        day_offset: config.day_offset(),
+        split_days: config.day.split,
+    }
+}
+
+pub fn handle_config_command(command: ConfigCommands) {
+    let result = match command {
+        ConfigCommands::Get { key } => config().get(&key).map(|value| match value {
+            Some(value) => println!("{value}"),
+            None => println!("(not set)"),
+        }),
+        ConfigCommands::Set { key, value } => {
+            let mut config = config();
+            config
+                .set(&key, Some(&value))
+                .and_then(|_| save(&config))
+                .map(|_| {
+                    println!("{key} = {value}");
+                    // 数据库在启动时打开
+                    if key == "db_path" {
+                        println!("The database path takes effect on the next start.");
+                    }
+                })
+        }
+        ConfigCommands::Unset { key } => {
+            let mut config = config();
+            config
+                .set(&key, None)
+                .and_then(|_| save(&config))
+                .map(|_| println!("{key} reset to default."))
+        }
+        ConfigCommands::List => {
+            for (key, value) in config().entries() {
+                println!(
+                    "{:<24}{}",
+                    key,
+                    value.unwrap_or_else(|| "(not set)".to_string())
+                );
+            }
+            Ok(())
+        }
+        ConfigCommands::Path => config_path().map(|path| println!("{}", path.display())),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
@@ -1,14 +1,37 @@
-use super::{app::TimerStatus, utils::get_datetime};
-use crate::core::timeline::Timeline;
-use chrono::TimeDelta;
+use super::{
+    app::TimerStatus,
+    config::{calendar, color_code, config, time_zone},
+    render::OutputFormat,
+    table::Table,
+    utils::{format_date, get_date_str, get_datetime, get_time_str},
+};
+use crate::core::{
+    budget::{Budget, BudgetStatus, BurnDownDay},
+    calendar::Calendar,
+    chart::{ChartDay, HEAT_LEVELS, heat_level, weeks},
+    client::Client,
+    goal::{Goal, GoalHistory, GoalKind, GoalProgress},
+    invoice::Invoice,
+    project::Project,
+    rate::{Earnings, Rate, round_amount},
+    reminder::Reminder,
+    rounding::RoundingPolicy,
+    search::{HIGHLIGHT_END, HIGHLIGHT_START, SearchHit},
+    standup::StandupItem,
+    tag::{Tag, is_in_tag_subtree, tag_depth, tag_leaf},
+    task_template::TaskTemplate,
+    time_slice::TimeSlice,
+    timeline::Timeline,
+};
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
 
-pub fn display_current_timer_status(status: &TimerStatus) {
+pub fn display_current_timer_status(status: &TimerStatus, format: OutputFormat) {
     let start_datetime = get_datetime(status.start_time);
-    let date_str = start_datetime.format("%Y-%m-%d").to_string();
-    let start_str = start_datetime.format("%H:%M:%S").to_string();
-    let end_str = status.end_time.map_or("None".to_string(), |end| {
-        get_datetime(end).format("%H:%M:%S").to_string()
-    });
+    let date_str = get_date_str(status.start_time);
+    let start_str = get_time_str(status.start_time);
+    let end_str = status.end_time.map_or("None".to_string(), get_time_str);
 
     let empty = "".to_string();
     let desc = status.desc.as_ref().unwrap_or(&empty);
@@ -22,60 +45,92 @@ pub fn display_current_timer_status(status: &TimerStatus) {
     let seconds = duration.num_seconds() % 60;
     let duration_str = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
 
-    // ----Current Timer--------------------------------
-    // Date          Start        End        Duration
-    // 2025/02/13    15:46:51  -  17:03:50   01:16:59
-    // -------------------------------------------------
-    // > improved display functionality
-    println!("----Current Timer--------------------------------");
-    println!("Date          Start        End        Duration");
-    if end_str == "None" {
-        println!(
-            "{}    {}  -  {}       {}",
-            date_str, start_str, end_str, duration_str
-        );
-    } else {
-        println!(
-            "{}    {}  -  {}   {}",
-            date_str, start_str, end_str, duration_str
-        );
-    }
-
-    println!("-------------------------------------------------");
-    println!("> {}", desc);
+    // ----Current Timer----------------------------------------------
+    // Date        Start     End       Duration  Description
+    // 2025/02/13  15:46:51  17:03:50  01:16:59  improved display functionality
+    // ---------------------------------------------------------------
+    let mut table = Table::new(
+        "Current Timer",
+        &["Date", "Start", "End", "Duration", "Description"],
+    )
+    .wrap(4);
+    table.row(vec![
+        date_str,
+        start_str,
+        end_str,
+        duration_str,
+        desc.trim().to_string(),
+    ]);
+    table.print(format);
 }
 
+#[allow(non_snake_case, clippy::needless_return)]
 fn formatTimeDelta(delta: TimeDelta) -> (i64, i64, i64) {
     let hours = delta.num_hours();
     let minutes = delta.num_minutes() % 60;
     let seconds = delta.num_seconds() % 60;
     return (hours, minutes, seconds);
 }
-pub fn display_timer_sheet(timeline: &Timeline) {
+
+// 将毫秒时长格式化为 HH:MM:SS
+pub fn format_duration(ms: u64) -> String {
+    let (hours, minutes, seconds) = formatTimeDelta(TimeDelta::milliseconds(ms as i64));
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+// 将毫秒时长格式化为简短的形式，例如 1h 30m、45m，不足一分钟时为 <1m
+pub fn format_duration_short(ms: u64) -> String {
+    let (hours, minutes, _) = formatTimeDelta(TimeDelta::milliseconds(ms as i64));
+    match (hours, minutes) {
+        (0, 0) => "<1m".to_string(),
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+pub fn display_timer_sheet(timeline: &Timeline, format: OutputFormat) {
     // filter:
-    // ---------------------------------------------------------------------------------------------------------
-    // Date          Start        End        Duration        Tags        Description
-    // 2025/02/13    15:46:51  -  17:03:50   01:16:59        code        测试效果
-    // ---------------------------------------------------------------------------------------------------------
-    // Total:        100days                 100hr
+    // ----Timer Sheet-------------------------------------------------------
+    // ID  Date        Start     End       Duration  Tags      Description
+    // 1   2025/02/13  15:46:51  17:03:50  01:16:59  code      测试效果
+    // ----------------------------------------------------------------------
+    // Total:          100days             100hour 0min 0sec
 
-    println!("filter:");
-    println!(
-        "---------------------------------------------------------------------------------------------------------"
-    );
-    println!("ID    Date          Start        End        Duration        Tags        Description");
+    if format.is_table() {
+        println!("filter:");
+    }
+    // 终端表格在描述后标出记录时的时区，其它格式单独输出一列
+    let mut headers = vec![
+        "ID",
+        "Date",
+        "Start",
+        "End",
+        "Duration",
+        "Tags",
+        "Description",
+    ];
+    if !format.is_table() {
+        headers.push("Zone");
+    }
+    let mut table = Table::new("Timer Sheet", &headers)
+        .numeric(0)
+        .max_width(5, 24)
+        .wrap(6);
     let mut total_days = 0;
 
     let mut total_time = TimeDelta::default();
     let mut prev_date = "".to_string();
+    let display_zone = time_zone();
+    let calendar = calendar();
     for time_slice in &timeline.list {
         if let Ok(time_info) = timeline.get_time_info(time_slice.id) {
             let time_slice = time_info.time_slice;
             let start_datetime = get_datetime(time_slice.start_time);
-            let date_str = start_datetime.format("%Y-%m-%d").to_string();
-            let start_str = start_datetime.format("%H:%M:%S").to_string();
+            // 按每天开始的时刻分组，深夜的记录与前一天归在一起
+            let date_str = format_date(calendar.date_of(time_slice.start_time));
+            let start_str = get_time_str(time_slice.start_time);
             let end_str = if let Some(end) = time_slice.end_time {
-                get_datetime(end).format("%H:%M:%S").to_string()
+                get_time_str(end)
             } else {
                 "None".to_string()
             };
@@ -90,34 +145,1140 @@ pub fn display_timer_sheet(timeline: &Timeline) {
             total_time += duration;
             let (hours, minutes, seconds) = formatTimeDelta(duration);
             let duration_str = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
+            // 项目以 @project 的形式展示在标签前面
             let tags = time_info
-                .tag
-                .unwrap_or_default()
+                .project
+                .map(|p| format!("@{}", p.name))
                 .into_iter()
-                .map(|t| t.name)
+                .chain(
+                    time_info
+                        .tag
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|t| t.name),
+                )
                 .collect::<Vec<String>>()
                 .join(" ");
-            let desc = time_info.desc.unwrap_or_default();
-            let print_date = if date_str == prev_date {
+            let mut desc = time_info.desc.unwrap_or_default().trim().to_string();
+            // 在其它时区记录的切片标出记录时的时区
+            if format.is_table()
+                && let Some(zone) = &time_slice.time_zone
+                && zone != display_zone.name()
+            {
+                desc = format!("{desc} [{zone}]");
+            }
+            // 终端表格中同一天只在第一条记录上标出日期
+            let print_date = if date_str == prev_date && format.is_table() {
                 "".to_string()
             } else {
-                total_days += 1;
+                if date_str != prev_date {
+                    total_days += 1;
+                }
                 date_str.clone()
             };
-            println!(
-                "{:<4}  {:<10}    {start_str}  -  {end_str}   {duration_str}        {:<10}  {}",
-                time_slice.id,
+            let mut row = vec![
+                time_slice.id.to_string(),
                 print_date,
+                start_str,
+                end_str,
+                duration_str,
                 tags,
-                desc.trim()
-            );
+                desc,
+            ];
+            if !format.is_table() {
+                row.push(time_slice.time_zone.clone().unwrap_or_default());
+            }
+            table.row(row);
             prev_date = date_str;
         }
     }
-    println!(
-        "---------------------------------------------------------------------------------------------------------"
-    );
     let (hours, minutes, seconds) = formatTimeDelta(total_time);
     let total_time_str = format!("{}hour {}min {}sec", hours, minutes, seconds);
-    println!("Total:        {total_days}days                 {total_time_str}")
+    table.footer(vec![
+        "Total:".to_string(),
+        format!("{total_days}days"),
+        String::new(),
+        String::new(),
+        total_time_str,
+    ]);
+    table.print(format);
+}
+
+// roots 不为空时只展示这些标签的子树，有取整规则时同时展示原始时长与取整后的时长
+pub fn display_tag_tree(
+    timeline: &Timeline,
+    roots: Option<&[String]>,
+    rounding: Option<&RoundingPolicy>,
+    format: OutputFormat,
+) {
+    // ----Tags---------------------------
+    // Tag            Raw       Rounded
+    // client         03:00:00  03:00:00
+    //   acme         03:00:00  03:00:00
+    //     backend    02:00:00  02:00:00
+    //     frontend   01:00:00  01:00:00
+    // -----------------------------------
+    // Total:         03:00:00  03:00:00
+    // 没有取整规则时终端表格不显示表头
+    let mut table = match rounding {
+        Some(_) => Table::new("Tags", &["Tag", "Raw", "Rounded"]),
+        None => Table::new("Tags", &["Tag", "Time"]).hide_header(),
+    }
+    .max_width(0, 40);
+    let durations = |slices: &[&TimeSlice]| {
+        let total = slices.iter().map(|slice| slice.get_len()).sum();
+        let rounded = rounding.map(|rounding| {
+            format_duration(rounding.rounded_total(timeline, slices.iter().copied()))
+        });
+        std::iter::once(format_duration(total)).chain(rounded)
+    };
+    let config = config();
+    for (path, slices) in timeline.tag_slices() {
+        if roots.is_some_and(|roots| !roots.iter().any(|root| is_in_tag_subtree(&path, root))) {
+            continue;
+        }
+        // 终端表格按层级缩进，其它格式使用完整的标签路径
+        let name = if format.is_table() {
+            let leaf = match config.tag_color(&path, &timeline.tag_colors) {
+                Some(code) => paint(tag_leaf(&path), code),
+                None => tag_leaf(&path).to_string(),
+            };
+            format!("{}{}", "  ".repeat(tag_depth(&path)), leaf)
+        } else {
+            path.clone()
+        };
+        table.row(std::iter::once(name).chain(durations(&slices)).collect());
+    }
+    let slices: Vec<&TimeSlice> = timeline.list.iter().collect();
+    table.footer(
+        std::iter::once("Total:".to_string())
+            .chain(durations(&slices))
+            .collect(),
+    );
+    table.print(format);
+}
+
+// 为文字加上终端颜色
+fn paint(text: &str, code: &str) -> String {
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+pub fn display_projects(projects: &[Project], format: OutputFormat) {
+    // ----Projects----------------
+    // Name       Client  Status
+    // acme-site  acme    active
+    // ----------------------------
+    let mut table = Table::new("Projects", &["Name", "Client", "Status"])
+        .max_width(0, 30)
+        .max_width(1, 30);
+    for project in projects {
+        let status = if project.archived {
+            "archived"
+        } else {
+            "active"
+        };
+        table.row(vec![
+            project.name.clone(),
+            project.client.clone().unwrap_or("-".to_string()),
+            status.to_string(),
+        ]);
+    }
+    table.print(format);
+}
+
+pub fn display_clients(clients: &[Client], format: OutputFormat) {
+    let mut table = Table::new("Clients", &["Name", "Status"]).max_width(0, 40);
+    for client in clients {
+        let status = if client.archived {
+            "archived"
+        } else {
+            "active"
+        };
+        table.row(vec![client.name.clone(), status.to_string()]);
+    }
+    table.print(format);
+}
+
+// totals 为项目 ID 到总时长（毫秒）的映射
+pub fn display_project_totals(
+    projects: &[Project],
+    totals: &BTreeMap<u64, u64>,
+    format: OutputFormat,
+) {
+    // ----Project Totals----------
+    // acme           03:00:00
+    //   acme-site    02:00:00
+    // (no client)    00:30:00
+    //   internal     00:30:00
+    // ----------------------------
+    // Total:         03:30:00
+    let mut by_client: BTreeMap<Option<&str>, Vec<&Project>> = BTreeMap::new();
+    for project in projects {
+        by_client
+            .entry(project.client.as_deref())
+            .or_default()
+            .push(project);
+    }
+    let total_of = |project: &Project| totals.get(&project.id).copied().unwrap_or(0);
+
+    // 终端表格按客户分组缩进展示，其它格式每个项目一行并带上所属客户
+    let mut table = if format.is_table() {
+        Table::new("Project Totals", &["Project", "Time"])
+            .max_width(0, 40)
+            .hide_header()
+    } else {
+        Table::new("Project Totals", &["Client", "Project", "Time"])
+    };
+    let mut total = 0;
+    for (client, projects) in by_client {
+        let client_total: u64 = projects.iter().map(|p| total_of(p)).sum();
+        total += client_total;
+        if !format.is_table() {
+            for project in projects {
+                table.row(vec![
+                    client.unwrap_or_default().to_string(),
+                    project.name.clone(),
+                    format_duration(total_of(project)),
+                ]);
+            }
+            continue;
+        }
+        table.row(vec![
+            client.unwrap_or("(no client)").to_string(),
+            format_duration(client_total),
+        ]);
+        for project in projects {
+            table.row(vec![
+                format!("  {}", project.name),
+                format_duration(total_of(project)),
+            ]);
+        }
+    }
+    table.footer(vec!["Total:".to_string(), format_duration(total)]);
+    table.print(format);
+}
+
+pub fn display_rates(rates: &[Rate], format: OutputFormat) {
+    // ----Rates---------------------------------------
+    // ID  Target   Name       Rate/h  From
+    // 1   project  acme-site  100     2025-01-01
+    // ------------------------------------------------
+    let mut table = Table::new("Rates", &["ID", "Target", "Name", "Rate/h", "From"])
+        .numeric(0)
+        .numeric(3)
+        .max_width(2, 30);
+    for rate in rates {
+        let from = if rate.effective_from == 0 {
+            "-".to_string()
+        } else {
+            get_date_str(rate.effective_from)
+        };
+        table.row(vec![
+            rate.id.to_string(),
+            rate.target.as_str().to_string(),
+            rate.target_name.clone(),
+            rate.hourly_rate.to_string(),
+            from,
+        ]);
+    }
+    table.print(format);
+}
+
+pub fn display_earnings(earnings: &Earnings, currency: &str, precision: u32, format: OutputFormat) {
+    // ----Earnings----------------------------------------------
+    // Project/Tag    Raw       Rounded   Rate/h  Amount
+    // @acme-site     01:52:00  02:00:00  100.00  200.00 USD
+    // ----------------------------------------------------------
+    // Billable:      02:22:00  02:30:00          200.00 USD
+    // Non-billable:  00:30:00
+    // Unrated:       00:30:00
+    let amount =
+        |amount: f64| format!("{:.*}", precision as usize, round_amount(amount, precision));
+    let mut table = Table::new(
+        "Earnings",
+        &["Project/Tag", "Raw", "Rounded", "Rate/h", "Amount"],
+    )
+    .max_width(0, 30);
+    for (label, line) in &earnings.lines {
+        table.row(vec![
+            label.clone(),
+            format_duration(line.duration),
+            format_duration(line.rounded),
+            line.rate.map_or("mixed".to_string(), amount),
+            format!("{} {}", amount(line.amount), currency),
+        ]);
+    }
+    table.footer(vec![
+        "Billable:".to_string(),
+        format_duration(earnings.billable),
+        format_duration(earnings.billable_rounded),
+        String::new(),
+        format!("{} {}", amount(earnings.total), currency),
+    ]);
+    table.footer(vec![
+        "Non-billable:".to_string(),
+        format_duration(earnings.non_billable),
+    ]);
+    if earnings.unrated > 0 {
+        table.footer(vec![
+            "Unrated:".to_string(),
+            format_duration(earnings.unrated),
+        ]);
+    }
+    table.print(format);
+}
+
+pub fn display_invoices(invoices: &[Invoice], format: OutputFormat) {
+    // ----Invoices-----------------------------------
+    // Number    Date        Bill to  Total
+    // INV-0001  2025-03-01  acme     1200.00 USD
+    // -----------------------------------------------
+    let mut table =
+        Table::new("Invoices", &["Number", "Date", "Bill to", "Total"]).max_width(2, 40);
+    for invoice in invoices {
+        let bill_to = [invoice.client.as_deref(), invoice.project.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<&str>>()
+            .join(" / ");
+        let voided = if invoice.voided { " (voided)" } else { "" };
+        table.row(vec![
+            invoice.number.clone(),
+            get_date_str(invoice.created_at),
+            bill_to,
+            format!("{:.2} {}{}", invoice.total, invoice.currency, voided),
+        ]);
+    }
+    table.print(format);
+}
+
+// 用方块字符绘制进度条，ratio 超过 1 时按满格绘制
+pub fn progress_bar(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("[{}{}]", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn goal_target_str(goal: &Goal) -> String {
+    let kind = match goal.kind {
+        GoalKind::Min => "",
+        GoalKind::Max => "max ",
+    };
+    format!("{}{}", kind, format_duration(goal.target))
+}
+
+pub fn display_goals(goals: &[Goal], format: OutputFormat) {
+    // ----Goals-------------------------------
+    // ID  Tag        Target        Period
+    // 1   #deepwork  20:00:00      week
+    // 2   #meeting   max 02:00:00  day
+    // ----------------------------------------
+    let mut table = Table::new("Goals", &["ID", "Tag", "Target", "Period"])
+        .numeric(0)
+        .max_width(1, 30);
+    for goal in goals {
+        table.row(vec![
+            goal.id.to_string(),
+            format!("#{}", goal.tag),
+            goal_target_str(goal),
+            goal.period.as_str().to_string(),
+        ]);
+    }
+    table.print(format);
+}
+
+// 目标在某个周期内的完成情况，用于终端以外的格式
+fn goal_progress_cells(goal: &Goal, progress: &GoalProgress) -> Vec<String> {
+    vec![
+        goal.id.to_string(),
+        goal.tag.clone(),
+        goal.kind.as_str().to_string(),
+        format_duration(goal.target),
+        goal.period.as_str().to_string(),
+        format_date(progress.period_start),
+        format_duration(progress.done),
+        ((progress.ratio() * 100.0).round() as u64).to_string(),
+    ]
+}
+
+const GOAL_PROGRESS_HEADERS: [&str; 8] = [
+    "ID",
+    "Tag",
+    "Kind",
+    "Target",
+    "Period",
+    "Period Start",
+    "Done",
+    "Percent",
+];
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+pub fn display_goal_progress(progress: &[(&Goal, GoalProgress)], format: OutputFormat) {
+    if !format.is_table() {
+        let headers = [&GOAL_PROGRESS_HEADERS[..], &["Met"]].concat();
+        let mut table = Table::new("Goals", &headers).numeric(0).numeric(7);
+        for (goal, progress) in progress {
+            let mut row = goal_progress_cells(goal, progress);
+            row.push(yes_no(progress.is_met()));
+            table.row(row);
+        }
+        table.print(format);
+        return;
+    }
+    // ----Goals----------------------------------------------------------
+    // #deepwork     [████████░░░░░░░░░░░░]  40%  08:00:00 / 20:00:00      week
+    // #meeting      [██████████████████░░]  90%  01:48:00 / max 02:00:00  day
+    // -------------------------------------------------------------------
+    println!("----Goals----------------------------------------------------------");
+    for (goal, progress) in progress {
+        // max 目标超出时标记出来
+        let mark = if goal.kind == GoalKind::Max && !progress.is_met() {
+            " !"
+        } else {
+            ""
+        };
+        println!(
+            "{:<14}{}  {:>3}%  {} / {:<14}{}{}",
+            format!("#{}", goal.tag),
+            progress_bar(progress.ratio(), 20),
+            (progress.ratio() * 100.0).round() as u64,
+            format_duration(progress.done),
+            goal_target_str(goal),
+            goal.period.as_str(),
+            mark
+        );
+    }
+    println!("-------------------------------------------------------------------");
+}
+
+pub fn display_goal_history(histories: &[(&Goal, GoalHistory)], format: OutputFormat) {
+    // 其它格式每个周期一行，进行中的周期不标出是否达成
+    if !format.is_table() {
+        let headers = [
+            &GOAL_PROGRESS_HEADERS[..],
+            &["Met", "Streak", "Best", "Misses"],
+        ]
+        .concat();
+        let mut table = Table::new("Goal History", &headers)
+            .numeric(0)
+            .numeric(7)
+            .numeric(9)
+            .numeric(10)
+            .numeric(11);
+        for (goal, history) in histories {
+            for (ind, progress) in history.periods.iter().enumerate() {
+                let mut row = goal_progress_cells(goal, progress);
+                row.push(if ind + 1 == history.periods.len() {
+                    String::new()
+                } else {
+                    yes_no(progress.is_met())
+                });
+                row.push(history.current_streak.to_string());
+                row.push(history.best_streak.to_string());
+                row.push(history.misses.to_string());
+                table.row(row);
+            }
+        }
+        table.print(format);
+        return;
+    }
+    // ----Goal History---------------------------------------------------
+    // #deepwork 20:00:00 per week
+    //   ✔ ✔ ✘ ✔ ✔ ✔ ✔ …      streak 4   best 4   misses 1
+    //   2025-01-06  [████████████████████] 105%  21:00:00
+    //   ...
+    // -------------------------------------------------------------------
+    println!("----Goal History---------------------------------------------------");
+    for (goal, history) in histories {
+        println!(
+            "#{} {} per {}",
+            goal.tag,
+            goal_target_str(goal),
+            goal.period.as_str()
+        );
+        // 最后一个为进行中的周期
+        let marks = history
+            .periods
+            .iter()
+            .enumerate()
+            .map(|(ind, progress)| {
+                if ind + 1 == history.periods.len() {
+                    "…"
+                } else if progress.is_met() {
+                    "✔"
+                } else {
+                    "✘"
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join(" ");
+        println!(
+            "  {}    streak {}   best {}   misses {}",
+            marks, history.current_streak, history.best_streak, history.misses
+        );
+        for progress in &history.periods {
+            println!(
+                "  {}  {} {:>3}%  {}",
+                format_date(progress.period_start),
+                progress_bar(progress.ratio(), 20),
+                (progress.ratio() * 100.0).round() as u64,
+                format_duration(progress.done)
+            );
+        }
+    }
+    println!("-------------------------------------------------------------------");
+}
+
+// 提醒在后台线程中输出，交互模式下交给 rustyline 输出，因此只返回文本
+pub fn format_reminder(reminder: &Reminder) -> String {
+    match reminder {
+        Reminder::Idle { idle } => format!(
+            "Reminder: no timer has been running for {}, start one with \"s\".",
+            format_duration(*idle)
+        ),
+        Reminder::LongRunning {
+            desc,
+            elapsed,
+            usual,
+        } => format!(
+            "Reminder: the timer \"{}\" has been running for {}, it usually takes {}. Stop it with \"e\".",
+            desc.as_deref().unwrap_or_default().trim(),
+            format_duration(*elapsed),
+            format_duration(*usual)
+        ),
+    }
+}
+
+// 剩余预算，超出时显示为负数
+fn format_remaining(remaining: i64) -> String {
+    if remaining < 0 {
+        format!("-{}", format_duration(remaining.unsigned_abs()))
+    } else {
+        format_duration(remaining as u64)
+    }
+}
+
+// 启动计时器时展示相关预算的剩余时长，duration 为计时器的预定时长
+pub fn display_budget_remaining(budgets: &[(Budget, BudgetStatus)], duration: Option<u64>) {
+    // Budget @website (total): 12:00:00 left of 40:00:00
+    for (budget, status) in budgets {
+        let remaining = status.remaining();
+        println!(
+            "Budget {} ({}): {} left of {}",
+            budget.label(),
+            budget.period.as_str(),
+            format_remaining(remaining),
+            format_duration(budget.amount)
+        );
+        if remaining <= 0 {
+            println!(
+                "Warning: the budget of {} is already used up.",
+                budget.label()
+            );
+        } else if let Some(duration) = duration
+            && duration > remaining as u64
+        {
+            println!(
+                "Warning: this timer will exceed the budget of {} by {}.",
+                budget.label(),
+                format_duration(duration - remaining as u64)
+            );
+        }
+    }
+}
+
+pub fn display_budgets(budgets: &[(Budget, BudgetStatus)], format: OutputFormat) {
+    // ----Budgets---------------------------------------------------------------------
+    // ID  Target    Period  Budget    Used      Remaining
+    // 1   @website  total   40:00:00  28:00:00  12:00:00   [██████████████░░░░░░]
+    // --------------------------------------------------------------------------------
+    let mut table = Table::new(
+        "Budgets",
+        &[
+            "ID",
+            "Target",
+            "Period",
+            "Budget",
+            "Used",
+            "Remaining",
+            "Progress",
+        ],
+    )
+    .numeric(0)
+    .max_width(1, 30);
+    for (budget, status) in budgets {
+        let ratio = if budget.amount == 0 {
+            1.0
+        } else {
+            status.used as f64 / budget.amount as f64
+        };
+        table.row(vec![
+            budget.id.to_string(),
+            budget.label(),
+            budget.period.as_str().to_string(),
+            format_duration(budget.amount),
+            format_duration(status.used),
+            format_remaining(status.remaining()),
+            progress_bar(ratio, 20),
+        ]);
+    }
+    table.print(format);
+}
+
+pub fn display_budget_burn_down(burn_downs: &[(Budget, Vec<BurnDownDay>)], format: OutputFormat) {
+    if !format.is_table() {
+        let mut table = Table::new(
+            "Budget Burn-down",
+            &[
+                "ID",
+                "Target",
+                "Period",
+                "Budget",
+                "Date",
+                "Used",
+                "Remaining",
+            ],
+        )
+        .numeric(0);
+        for (budget, days) in burn_downs {
+            for day in days {
+                table.row(vec![
+                    budget.id.to_string(),
+                    budget.label(),
+                    budget.period.as_str().to_string(),
+                    format_duration(budget.amount),
+                    format_date(day.date),
+                    format_duration(day.used),
+                    format_remaining(day.remaining),
+                ]);
+            }
+        }
+        table.print(format);
+        return;
+    }
+    // ----Budget Burn-down-----------------------------------------------
+    // @website 40:00:00 (total)
+    //   2025-01-06  08:00:00  [████████████████░░░░]  32:00:00 left
+    //   2025-01-07  20:00:00  [██████░░░░░░░░░░░░░░]  12:00:00 left
+    // -------------------------------------------------------------------
+    println!("----Budget Burn-down-----------------------------------------------");
+    for (budget, days) in burn_downs {
+        println!(
+            "{} {} ({})",
+            budget.label(),
+            format_duration(budget.amount),
+            budget.period.as_str()
+        );
+        if days.is_empty() {
+            println!("  no time recorded in this period");
+        }
+        for day in days {
+            let ratio = if budget.amount == 0 {
+                0.0
+            } else {
+                day.remaining as f64 / budget.amount as f64
+            };
+            println!(
+                "  {}  {}  {}  {} left",
+                format_date(day.date),
+                format_duration(day.used),
+                progress_bar(ratio, 20),
+                format_remaining(day.remaining)
+            );
+        }
+    }
+    println!("-------------------------------------------------------------------");
+}
+
+pub fn display_search_results(timeline: &Timeline, hits: &[SearchHit], format: OutputFormat) {
+    // ----Search----------------------------------------------
+    // ID  Date        Start     Duration  Description
+    // 12  2025-02-13  15:46:51  01:16:59  …测试效果…
+    // --------------------------------------------------------
+    // 3 matches
+    let mut table = Table::new(
+        "Search",
+        &["ID", "Date", "Start", "Duration", "Description"],
+    )
+    .numeric(0)
+    .wrap(4);
+    let highlight_start = match config().highlight_color() {
+        Some(code) => format!("\x1b[1;{code}m"),
+        None => "\x1b[1m".to_string(),
+    };
+    for hit in hits {
+        let Some(time_slice) = timeline
+            .list
+            .iter()
+            .find(|slice| slice.id == hit.time_slice_id)
+        else {
+            continue;
+        };
+        // 匹配的词以配置的颜色加粗高亮，关闭颜色时只加粗
+        let snippet = hit
+            .snippet
+            .replace(HIGHLIGHT_START, &highlight_start)
+            .replace(HIGHLIGHT_END, "\x1b[0m");
+        table.row(vec![
+            time_slice.id.to_string(),
+            get_date_str(time_slice.start_time),
+            get_time_str(time_slice.start_time),
+            format_duration(time_slice.get_len()),
+            snippet.trim().to_string(),
+        ]);
+    }
+    table.footer(vec![format!("{} matches", hits.len())]);
+    table.print(format);
+}
+
+pub fn display_tags(tags: &[Tag], format: OutputFormat) {
+    // ----Tags----------------
+    // Tag          Color
+    // client       blue
+    //   acme       -
+    // ------------------------
+    let mut tags: Vec<&Tag> = tags.iter().collect();
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    let config = config();
+    let mut table = Table::new("Tags", &["Tag", "Color"]).max_width(0, 40);
+    for tag in tags {
+        let name = if format.is_table() {
+            format!(
+                "{}{}",
+                "  ".repeat(tag_depth(&tag.name)),
+                tag_leaf(&tag.name)
+            )
+        } else {
+            tag.name.clone()
+        };
+        let color = match tag.color.as_deref() {
+            Some(color) => match color_code(color).filter(|_| config.colors.enabled) {
+                Some(code) => paint(color, code),
+                None => color.to_string(),
+            },
+            None => "-".to_string(),
+        };
+        table.row(vec![name, color]);
+    }
+    table.print(format);
+}
+
+pub fn display_task_templates(templates: &[TaskTemplate], format: OutputFormat) {
+    // ----Templates------------------------------------
+    // Name      Duration  Description
+    // standup   00:15:00  #meeting daily standup
+    // review              #code @acme code review
+    // -------------------------------------------------
+    let mut table = Table::new("Templates", &["Name", "Duration", "Description"])
+        .max_width(0, 24)
+        .wrap(2);
+    for template in templates {
+        table.row(vec![
+            template.name.clone(),
+            template.duration.map(format_duration).unwrap_or_default(),
+            template.desc.clone().unwrap_or_default(),
+        ]);
+    }
+    table.print(format);
+}
+
+// 终端的列数，输出被重定向时读取 COLUMNS 环境变量，都无法获取时按 80 列处理
+pub fn terminal_width() -> usize {
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+// 不足一格的部分，依次为 1/8 到 7/8 格
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+// 迷你图的八个高度
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// 没有设置颜色的标签在堆叠图中依次使用的颜色
+const CHART_PALETTE: [&str; 6] = ["cyan", "magenta", "yellow", "green", "blue", "red"];
+// 关闭颜色时堆叠图中依次使用的填充字符
+const CHART_FILLS: [char; 4] = ['█', '▓', '▒', '░'];
+
+// 用方块字符绘制长度为 cells 格的横条，精确到 1/8 格
+fn bar(cells: f64) -> String {
+    let eighths = (cells.max(0.0) * 8.0).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if let Some(partial) = (eighths % 8).checked_sub(1) {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+    bar
+}
+
+// 按终端宽度绘制的标题行，例如 "----Chart: Tags-------"
+fn chart_rule(title: &str, width: usize) {
+    println!("{:-<width$}", format!("----{title}"));
+}
+
+// 每个标签一行横条，子标签的时长汇总到祖先标签中，roots 不为空时只展示这些标签的子树
+pub fn display_tag_chart(
+    timeline: &Timeline,
+    roots: Option<&[String]>,
+    width: usize,
+    format: OutputFormat,
+) {
+    // ----Chart: Tags-----------------------------------------
+    // client          ███████████████████████████▌    03:00:00
+    //   acme          ██████████████████▍             02:00:00
+    // personal        █████████▏                      01:00:00
+    // --------------------------------------------------------
+    let rows: Vec<(String, u64)> = timeline
+        .tag_totals()
+        .into_iter()
+        .filter(|(path, _)| {
+            roots.is_none_or(|roots| roots.iter().any(|root| is_in_tag_subtree(path, root)))
+        })
+        .collect();
+    // 其它格式输出完整的标签路径与时长
+    if !format.is_table() {
+        let mut table = Table::new("Chart: Tags", &["Tag", "Time"]);
+        for (path, total) in &rows {
+            table.row(vec![path.clone(), format_duration(*total)]);
+        }
+        table.print(format);
+        return;
+    }
+    chart_rule("Chart: Tags", width);
+    if rows.is_empty() {
+        println!("No tagged time recorded.");
+    }
+    let label = |path: &str| format!("{}{}", "  ".repeat(tag_depth(path)), tag_leaf(path));
+    let label_width = rows
+        .iter()
+        .map(|(path, _)| label(path).chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(8)
+        + 2;
+    let bar_width = width.saturating_sub(label_width + 12).max(10);
+    let max = rows
+        .iter()
+        .map(|(_, total)| *total)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let config = config();
+    for (path, total) in &rows {
+        let cells = bar(*total as f64 / max as f64 * bar_width as f64);
+        let padding = " ".repeat(bar_width - cells.chars().count().min(bar_width));
+        let cells = match config.tag_color(path, &timeline.tag_colors) {
+            Some(code) => paint(&cells, code),
+            None => cells,
+        };
+        println!(
+            "{:<label_width$}{cells}{padding}  {}",
+            label(path),
+            format_duration(*total)
+        );
+    }
+    chart_rule("", width);
+}
+
+// 每天一行，按顶层标签堆叠的横条，最后列出图例
+pub fn display_daily_chart(
+    days: &[ChartDay],
+    tag_colors: &HashMap<String, String>,
+    width: usize,
+    format: OutputFormat,
+) {
+    // ----Chart: Daily----------------------------------------
+    // 2025/02/10 Mon  ████████████████▓▓▓▓▓▓▓▓▒▒▒     06:30:00
+    // 2025/02/11 Tue  ██████████▓▓▓▓                  03:00:00
+    // --------------------------------------------------------
+    // █ client  ▓ personal  ▒ (untagged)
+    // 按总时长从多到少排列标签，保证每天的堆叠顺序一致
+    let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+    for day in days {
+        for (tag, len) in &day.tags {
+            *totals.entry(tag).or_insert(0) += len;
+        }
+    }
+    let mut tags: Vec<(&str, u64)> = totals.into_iter().collect();
+    tags.sort_by_key(|(_, total)| Reverse(*total));
+
+    // 其它格式每天一行，每个顶层标签一列
+    if !format.is_table() {
+        let headers: Vec<&str> = ["Date", "Total"]
+            .into_iter()
+            .chain(tags.iter().map(|(tag, _)| *tag))
+            .collect();
+        let mut table = Table::new("Chart: Daily", &headers);
+        for day in days {
+            let mut row = vec![format_date(day.date), format_duration(day.total())];
+            row.extend(
+                tags.iter()
+                    .map(|(tag, _)| format_duration(day.tags.get(*tag).copied().unwrap_or(0))),
+            );
+            table.row(row);
+        }
+        table.print(format);
+        return;
+    }
+
+    let config = config();
+    let styles: Vec<(char, Option<&str>)> = tags
+        .iter()
+        .enumerate()
+        .map(|(ind, (tag, _))| {
+            if !config.colors.enabled {
+                return (CHART_FILLS[ind % CHART_FILLS.len()], None);
+            }
+            let code = config
+                .tag_color(tag, tag_colors)
+                .or_else(|| color_code(CHART_PALETTE[ind % CHART_PALETTE.len()]));
+            ('█', code)
+        })
+        .collect();
+
+    let label = |day: &ChartDay| format!("{} {}", format_date(day.date), day.date.format("%a"));
+    let label_width = days
+        .iter()
+        .map(|day| label(day).chars().count())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let bar_width = width.saturating_sub(label_width + 12).max(10);
+    let max = days.iter().map(ChartDay::total).max().unwrap_or(0).max(1);
+    let scale = bar_width as f64 / max as f64;
+
+    chart_rule("Chart: Daily", width);
+    for day in days {
+        // 按累计时长取整，避免每段单独取整后总长度出现偏差
+        let mut line = String::new();
+        let mut done = 0;
+        let mut cumulative = 0;
+        for ((tag, _), (fill, code)) in tags.iter().zip(&styles) {
+            cumulative += day.tags.get(*tag).copied().unwrap_or(0);
+            let end = (cumulative as f64 * scale).round() as usize;
+            if end > done {
+                let segment = fill.to_string().repeat(end - done);
+                line.push_str(&code.map_or(segment.clone(), |code| paint(&segment, code)));
+                done = end;
+            }
+        }
+        println!(
+            "{:<label_width$}{line}{}  {}",
+            label(day),
+            " ".repeat(bar_width.saturating_sub(done)),
+            format_duration(day.total())
+        );
+    }
+    chart_rule("", width);
+    let legend: Vec<String> = tags
+        .iter()
+        .zip(&styles)
+        .map(|((tag, _), (fill, code))| {
+            let fill = fill.to_string();
+            format!(
+                "{} {}",
+                code.map_or(fill.clone(), |code| paint(&fill, code)),
+                tag
+            )
+        })
+        .collect();
+    if !legend.is_empty() {
+        println!("{}", legend.join("  "));
+    }
+}
+
+// 每天总时长的迷你图，天数超过终端宽度时只展示最近的部分
+pub fn display_sparkline(days: &[ChartDay], width: usize, format: OutputFormat) {
+    // ----Chart: Daily Totals---------------------------------
+    // 2025/01/20 - 2025/02/18
+    // ▂▅▇ █▃▁▂▅▇▆█▃  ▂▅▇▆█▃▁▂▅▇▆  ▃
+    // max 08:00:00  avg 04:12:00  total 126:00:00
+    // --------------------------------------------------------
+    // 其它格式输出每天的总时长，不按终端宽度截取
+    if !format.is_table() {
+        let mut table = Table::new("Chart: Daily Totals", &["Date", "Total"]);
+        for day in days {
+            table.row(vec![format_date(day.date), format_duration(day.total())]);
+        }
+        table.print(format);
+        return;
+    }
+    let days = &days[days.len().saturating_sub(width.max(1))..];
+    let totals: Vec<u64> = days.iter().map(ChartDay::total).collect();
+    let max = totals.iter().copied().max().unwrap_or(0);
+    let sum: u64 = totals.iter().sum();
+    let line: String = totals
+        .iter()
+        .map(|&total| {
+            if total == 0 {
+                ' '
+            } else {
+                let level = (total as f64 / max as f64 * 7.0).round() as usize;
+                SPARK_BLOCKS[level.min(7)]
+            }
+        })
+        .collect();
+
+    chart_rule("Chart: Daily Totals", width);
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        println!("{} - {}", format_date(first.date), format_date(last.date));
+    }
+    let line = match config().highlight_color() {
+        Some(code) => paint(&line, code),
+        None => line,
+    };
+    println!("{line}");
+    println!(
+        "max {}  avg {}  total {}",
+        format_duration(max),
+        format_duration(sum / days.len().max(1) as u64),
+        format_duration(sum)
+    );
+    chart_rule("", width);
+}
+
+// 热力图的深浅，第一个为没有记录的日期
+const HEAT_BLOCKS: [char; HEAT_LEVELS + 1] = ['·', '░', '▒', '▓', '█'];
+
+// 按周排列的每日时长热力图，列数超过终端宽度时只展示最近的几周
+pub fn display_heatmap(days: &[ChartDay], calendar: &Calendar, width: usize, format: OutputFormat) {
+    // ----Heatmap: 2024-10-21 - 2025-10-19--------------------
+    //      Oct    Nov    Dec    Jan    Feb    Mar    Apr
+    // Mon  ·░▒··█▓·░··▒▒··▓·░··█·▒▒··▓·░··▒▓·█··░·▒·▓·░··█·
+    // ...
+    // --------------------------------------------------------
+    // Less · ░ ▒ ▓ █ More    126:00:00 on 45 days, busiest 2025-02-11 09:30:00
+    let max = days.iter().map(ChartDay::total).max().unwrap_or(0);
+    // 其它格式每天一行，Level 为热力图中的深浅（0 为没有记录）
+    if !format.is_table() {
+        let mut table = Table::new("Heatmap", &["Date", "Weekday", "Total", "Level"]).numeric(3);
+        for day in days {
+            table.row(vec![
+                format_date(day.date),
+                day.date.format("%a").to_string(),
+                format_duration(day.total()),
+                heat_level(day.total(), max).to_string(),
+            ]);
+        }
+        table.print(format);
+        return;
+    }
+    let weeks = weeks(days, calendar);
+    let weeks = &weeks[weeks.len().saturating_sub(width.saturating_sub(5).max(1))..];
+    let code = color_code("green").filter(|_| config().colors.enabled);
+    let cell = |level: usize| {
+        let block = HEAT_BLOCKS[level].to_string();
+        match code.filter(|_| level > 0) {
+            Some(code) => paint(&block, code),
+            None => block,
+        }
+    };
+
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        chart_rule(
+            &format!(
+                "Heatmap: {} - {}",
+                format_date(first.date),
+                format_date(last.date)
+            ),
+            width,
+        );
+    }
+    // 每月的第一周标出月份，放不下时跳过
+    let mut months = String::new();
+    let mut last_month = None;
+    for (column, week) in weeks.iter().enumerate() {
+        if let Some(first) = week.iter().flatten().next()
+            && last_month != Some(first.date.month())
+        {
+            last_month = Some(first.date.month());
+            if months.chars().count() <= column {
+                months.push_str(&" ".repeat(column - months.chars().count()));
+                months.push_str(&first.date.format("%b ").to_string());
+            }
+        }
+    }
+    println!("     {}", months.trim_end());
+    for (row, weekday) in calendar.weekdays().into_iter().enumerate() {
+        let line: String = weeks
+            .iter()
+            .map(|week| week[row].map_or(" ".to_string(), |day| cell(heat_level(day.total(), max))))
+            .collect();
+        println!("{:<5}{}", weekday.to_string(), line);
+    }
+    chart_rule("", width);
+    let legend: Vec<String> = (0..=HEAT_LEVELS).map(cell).collect();
+    let total: u64 = days.iter().map(ChartDay::total).sum();
+    let active = days.iter().filter(|day| day.total() > 0).count();
+    let busiest = days
+        .iter()
+        .max_by_key(|day| day.total())
+        .filter(|day| day.total() > 0)
+        .map_or(String::new(), |day| {
+            format!(
+                ", busiest {} {}",
+                format_date(day.date),
+                format_duration(day.total())
+            )
+        });
+    println!(
+        "Less {} More    {} on {} days{}",
+        legend.join(" "),
+        format_duration(total),
+        active,
+        busiest
+    );
+}
+
+// 站会摘要，终端中输出为 Markdown 列表，可以直接粘贴
+pub fn display_standup(date: NaiveDate, items: &[StandupItem], format: OutputFormat) {
+    if !format.is_table() {
+        let mut table = Table::new(
+            &format!("Standup {}", format_date(date)),
+            &["Item", "Tags", "Project", "Duration"],
+        );
+        for item in items {
+            table.row(vec![
+                item.title.trim().to_string(),
+                item.tags.join(" "),
+                item.project.clone().unwrap_or_default(),
+                format_duration(item.duration),
+            ]);
+        }
+        let total: u64 = items.iter().map(|item| item.duration).sum();
+        table.footer(vec![
+            "Total:".to_string(),
+            String::new(),
+            String::new(),
+            format_duration(total),
+        ]);
+        table.print(format);
+        return;
+    }
+    println!("**{} {}**", date.format("%A"), format_date(date));
+    println!();
+    if items.is_empty() {
+        println!("- Nothing recorded");
+        return;
+    }
+    for item in items {
+        let mut line = item.title.clone();
+        for tag in &item.tags {
+            line.push_str(&format!(" #{tag}"));
+        }
+        if let Some(project) = &item.project {
+            line.push_str(&format!(" @{project}"));
+        }
+        let line = line.trim();
+        let line = if line.is_empty() {
+            "(no description)"
+        } else {
+            line
+        };
+        println!("- {} ({})", line, format_duration_short(item.duration));
+    }
+    let total: u64 = items.iter().map(|item| item.duration).sum();
+    println!();
+    println!("Total: {}", format_duration_short(total));
 }
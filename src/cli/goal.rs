@@ -0,0 +1,126 @@
+use super::app::AppHandle;
+use super::config::calendar;
+use super::display::{display_goal_history, display_goal_progress, display_goals};
+use super::render::{OutputArgs, OutputFormat};
+use super::utils::{get_current_time, parse_goal, parse_tags};
+use crate::core::{goal::Goal, tag::is_in_tag_subtree, timeline::TimelineFilter};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum GoalCommands {
+    /// Add a goal, e.g. "20h #deepwork per week" or "max 2h #meeting per day"
+    Add {
+        #[arg(required = true, trailing_var_arg = true)]
+        spec: Vec<String>,
+    },
+    /// List goals
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Remove a goal by its ID
+    #[clap(alias = "rm")]
+    Remove { id: u64 },
+    /// Show the progress of all goals in the current period
+    Progress {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+pub fn handle_goal_command(app_handle: &AppHandle, command: GoalCommands) {
+    let result = match command {
+        GoalCommands::Add { spec } => parse_goal(&spec)
+            .map_err(anyhow::Error::msg)
+            .and_then(|(kind, target, tag, period)| {
+                app_handle.add_goal(&Goal {
+                    id: 0,
+                    tag,
+                    period,
+                    kind,
+                    target,
+                })
+            })
+            .map(|id| println!("Goal {id} added.")),
+        GoalCommands::List { output } => app_handle
+            .get_goals()
+            .map(|goals| display_goals(&goals, output.format)),
+        GoalCommands::Remove { id } => app_handle
+            .remove_goal(id)
+            .map(|_| println!("Goal {id} removed.")),
+        GoalCommands::Progress { output } => {
+            show_goal_progress(app_handle, output.format);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+// 展示当前周期内各目标的进度，正在运行的计时器同样计入
+pub fn show_goal_progress(app_handle: &AppHandle, format: OutputFormat) {
+    let Ok(goals) = app_handle.get_goals() else {
+        return;
+    };
+    if goals.is_empty() {
+        return;
+    }
+    let Ok(timeline) = app_handle.get_timeline(&TimelineFilter::default()) else {
+        return;
+    };
+    let running = app_handle
+        .get_current_timer_status()
+        .ok()
+        .filter(|status| status.end_time.is_none());
+
+    let calendar = calendar();
+    let today = calendar.today();
+    let progress = goals
+        .iter()
+        .map(|goal| {
+            let extra = running
+                .as_ref()
+                .filter(|status| {
+                    parse_tags(status.desc.as_deref().unwrap_or_default())
+                        .iter()
+                        .any(|tag| is_in_tag_subtree(tag, &goal.tag))
+                })
+                .map_or(0, |status| {
+                    get_current_time().saturating_sub(status.start_time)
+                });
+            (
+                goal,
+                goal.progress(
+                    &timeline,
+                    goal.period_start(today, &calendar),
+                    extra,
+                    &calendar,
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    if format.is_table() {
+        println!();
+    }
+    display_goal_progress(&progress, format);
+}
+
+// 展示各目标最近 periods 个周期的达成情况、连续达成与未达成次数
+pub fn show_goal_history(app_handle: &AppHandle, periods: usize, format: OutputFormat) {
+    let result = app_handle.get_goals().and_then(|goals| {
+        let timeline = app_handle.get_timeline(&TimelineFilter::default())?;
+        let calendar = calendar();
+        let today = calendar.today();
+        let histories = goals
+            .iter()
+            .map(|goal| (goal, goal.history(&timeline, today, periods, &calendar)))
+            .collect::<Vec<_>>();
+        display_goal_history(&histories, format);
+        Ok(())
+    });
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
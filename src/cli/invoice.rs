@@ -0,0 +1,368 @@
+use super::app::AppHandle;
+use super::cli::RoundingArgs;
+use super::config::{calendar, config};
+use super::display::{display_invoices, format_duration};
+use super::render::{OutputArgs, escape_html};
+use super::utils::{format_date, get_current_time, get_date_str, parse_date, parse_date_end};
+use crate::core::{
+    calendar::Calendar,
+    invoice::{Invoice, InvoiceGrouping},
+    rate::{Earnings, round_amount},
+    time_slice::TimeSlice,
+    timeline::{Timeline, TimelineFilter},
+};
+use anyhow::anyhow;
+use clap::{ArgGroup, Args, Subcommand, ValueEnum};
+
+#[derive(Subcommand, Debug)]
+pub enum InvoiceCommands {
+    /// Invoice the billable, not yet invoiced time of a client or project
+    Create(CreateInvoiceArgs),
+    /// List issued invoices
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Void an invoice, its time records can be invoiced again
+    Void { number: String },
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("target").required(true).multiple(true).args(["client", "project"])))]
+pub struct CreateInvoiceArgs {
+    #[arg(short, long)]
+    client: Option<String>,
+    #[arg(short, long)]
+    project: Option<String>,
+    /// First day included (e.g. "2025-02-01")
+    #[arg(long, value_parser = parse_date)]
+    from: Option<u64>,
+    /// Last day included (e.g. "2025-02-28")
+    #[arg(long, value_parser = parse_date_end)]
+    to: Option<u64>,
+    /// Group line items by "tag", "day" or "desc"
+    #[arg(short, long, default_value = "tag")]
+    group_by: InvoiceGrouping,
+    #[arg(short, long, value_enum, default_value_t = InvoiceFormat::Markdown)]
+    format: InvoiceFormat,
+    /// Write the invoice to a file instead of printing it
+    #[arg(short, long)]
+    output: Option<String>,
+    #[command(flatten)]
+    rounding: RoundingArgs,
+    /// Defaults to the configured currency
+    #[arg(long)]
+    currency: Option<String>,
+    /// Defaults to the configured precision
+    #[arg(long)]
+    precision: Option<u32>,
+    /// Render the invoice without recording it or marking time as invoiced
+    #[arg(long)]
+    preview: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum InvoiceFormat {
+    #[value(name = "md")]
+    Markdown,
+    /// HTML fragment that can be embedded into other pages
+    Html,
+    /// Standalone HTML page styled for printing or saving as PDF
+    Print,
+}
+
+pub fn handle_invoice_command(app_handle: &AppHandle, command: InvoiceCommands) {
+    let result = match command {
+        InvoiceCommands::Create(args) => create_invoice(app_handle, args),
+        InvoiceCommands::List { output } => app_handle
+            .get_invoices()
+            .map(|invoices| display_invoices(&invoices, output.format)),
+        InvoiceCommands::Void { number } => app_handle
+            .void_invoice(&number)
+            .map(|_| println!("Invoice {number} voided.")),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+fn create_invoice(app_handle: &AppHandle, args: CreateInvoiceArgs) -> anyhow::Result<()> {
+    let config = config();
+    let precision = args.precision.unwrap_or(config.precision);
+    let filter = TimelineFilter {
+        clients: args.client.clone().map(|client| vec![client]),
+        projects: args.project.clone().map(|project| vec![project]),
+        start_time: args.from,
+        end_time: args.to,
+        ..Default::default()
+    };
+    let mut timeline = app_handle.get_timeline(&filter)?;
+    // 只有已结束、计费且未开票的时间切片才能开票
+    timeline.retain(|_, slice| {
+        slice.billable && slice.invoice_id.is_none() && slice.end_time.is_some()
+    });
+    // 时间切片整体开票，跨越开票周期边界的切片会把周期外的时间一起计费
+    let crossing: Vec<String> = timeline
+        .list
+        .iter()
+        .filter(|slice| {
+            args.from.is_some_and(|from| slice.start_time < from)
+                || args
+                    .to
+                    .is_some_and(|to| slice.end_time.is_some_and(|end| end > to))
+        })
+        .map(|slice| slice.id.to_string())
+        .collect();
+    if !crossing.is_empty() {
+        return Err(anyhow!(
+            "Time records {} cross the invoice period, edit them or change --from/--to.",
+            crossing.join(", ")
+        ));
+    }
+
+    let rate_book = app_handle.get_rate_book()?;
+    let calendar = calendar();
+    let earnings = rate_book.earnings_by(&timeline, args.rounding.to_policy().as_ref(), |slice| {
+        line_label(&timeline, &calendar, slice, args.group_by)
+    });
+    if earnings.lines.is_empty() {
+        return Err(anyhow!("No billable time to invoice."));
+    }
+    if earnings.unrated > 0 {
+        println!(
+            "Warning: {} of billable time has no rate and is not invoiced.",
+            format_duration(earnings.unrated)
+        );
+    }
+
+    let mut invoice = Invoice {
+        id: 0,
+        number: format!("{} (preview)", app_handle.next_invoice_number()?),
+        client: args.client,
+        project: args.project,
+        start_time: args.from,
+        end_time: args.to,
+        created_at: get_current_time(),
+        total: round_amount(earnings.total, precision),
+        currency: args.currency.unwrap_or(config.currency),
+        voided: false,
+    };
+    let render = |invoice: &Invoice| match args.format {
+        InvoiceFormat::Markdown => render_markdown(invoice, &earnings, precision),
+        InvoiceFormat::Html => render_html(invoice, &earnings, precision),
+        InvoiceFormat::Print => render_print(invoice, &earnings, precision),
+    };
+    if !args.preview {
+        let slice_ids: Vec<u64> = earnings
+            .lines
+            .values()
+            .flat_map(|line| line.slice_ids.iter().copied())
+            .collect();
+        // 先写入临时文件再提交，写入失败时时间切片不会被标记到没有生成的发票上
+        let temp_path = args.output.as_ref().map(|path| format!("{path}.tmp"));
+        let created = app_handle.create_invoice(&invoice, &slice_ids, |invoice| {
+            if let Some(temp_path) = &temp_path {
+                std::fs::write(temp_path, render(invoice))?;
+            }
+            Ok(())
+        });
+        invoice = match created {
+            Ok(invoice) => invoice,
+            Err(e) => {
+                if let Some(temp_path) = &temp_path {
+                    let _ = std::fs::remove_file(temp_path);
+                }
+                return Err(e);
+            }
+        };
+        if let (Some(temp_path), Some(path)) = (&temp_path, &args.output) {
+            std::fs::rename(temp_path, path)?;
+            println!("Invoice {} written to {}.", invoice.number, path);
+            return Ok(());
+        }
+    }
+
+    let content = render(&invoice);
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            println!("Invoice {} written to {}.", invoice.number, path);
+        }
+        None => println!("{}", content),
+    }
+    Ok(())
+}
+
+// 发票明细的名称，按天分组时使用统计日历的日期，与其它报表的每天开始时刻一致
+fn line_label(
+    timeline: &Timeline,
+    calendar: &Calendar,
+    slice: &TimeSlice,
+    group_by: InvoiceGrouping,
+) -> String {
+    match group_by {
+        InvoiceGrouping::Tag => timeline
+            .tags
+            .get(&slice.id)
+            .and_then(|tags| tags.first())
+            .map(|tag| tag.name.clone())
+            .unwrap_or_else(|| "(untagged)".to_string()),
+        InvoiceGrouping::Day => format_date(calendar.date_of(slice.start_time)),
+        // 去掉描述中的 #tag 与 @project，只保留文字部分
+        InvoiceGrouping::Description => timeline
+            .desc
+            .get(&slice.id)
+            .map(|desc| {
+                desc.split_whitespace()
+                    .filter(|word| !word.starts_with('#') && !word.starts_with('@'))
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            })
+            .filter(|desc| !desc.is_empty())
+            .unwrap_or_else(|| "(no description)".to_string()),
+    }
+}
+
+fn hours(ms: u64) -> String {
+    format!("{:.2}", ms as f64 / 3_600_000.0)
+}
+
+fn money(amount: f64, precision: u32) -> String {
+    format!("{:.*}", precision as usize, round_amount(amount, precision))
+}
+
+fn period(invoice: &Invoice) -> String {
+    let from = invoice
+        .start_time
+        .map(get_date_str)
+        .unwrap_or_else(|| "…".to_string());
+    // end_time 为次日零点，展示时减去一毫秒得到包含的最后一天
+    let to = invoice
+        .end_time
+        .map(|end| get_date_str(end - 1))
+        .unwrap_or_else(|| "…".to_string());
+    format!("{} – {}", from, to)
+}
+
+fn bill_to(invoice: &Invoice) -> String {
+    [invoice.client.as_deref(), invoice.project.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<&str>>()
+        .join(" / ")
+}
+
+// 发票合计只统计各行的时长，没有费率而未计入发票的时间不算在内
+fn billed_duration(earnings: &Earnings) -> u64 {
+    earnings.lines.values().map(|line| line.rounded).sum()
+}
+
+fn render_markdown(invoice: &Invoice, earnings: &Earnings, precision: u32) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("# Invoice {}\n\n", invoice.number));
+    out.push_str(&format!(
+        "- **Date:** {}\n",
+        get_date_str(invoice.created_at)
+    ));
+    out.push_str(&format!("- **Bill to:** {}\n", bill_to(invoice)));
+    out.push_str(&format!("- **Period:** {}\n\n", period(invoice)));
+    out.push_str("| Item | Hours | Rate | Amount |\n");
+    out.push_str("| --- | ---: | ---: | ---: |\n");
+    for (label, line) in &earnings.lines {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            label.replace('|', "\\|"),
+            hours(line.rounded),
+            line.rate
+                .map_or("mixed".to_string(), |rate| money(rate, precision)),
+            money(line.amount, precision)
+        ));
+    }
+    out.push_str(&format!(
+        "| **Total** | **{}** | | **{} {}** |\n",
+        hours(billed_duration(earnings)),
+        money(invoice.total, precision),
+        invoice.currency
+    ));
+    out
+}
+
+fn render_html(invoice: &Invoice, earnings: &Earnings, precision: u32) -> String {
+    let mut out = String::new();
+    out.push_str("<div class=\"invoice\">\n");
+    out.push_str(&format!(
+        "  <h1>Invoice {}</h1>\n",
+        escape_html(&invoice.number)
+    ));
+    out.push_str("  <dl>\n");
+    out.push_str(&format!(
+        "    <dt>Date</dt><dd>{}</dd>\n",
+        get_date_str(invoice.created_at)
+    ));
+    out.push_str(&format!(
+        "    <dt>Bill to</dt><dd>{}</dd>\n",
+        escape_html(&bill_to(invoice))
+    ));
+    out.push_str(&format!(
+        "    <dt>Period</dt><dd>{}</dd>\n",
+        period(invoice)
+    ));
+    out.push_str("  </dl>\n");
+    out.push_str("  <table>\n");
+    out.push_str(
+        "    <thead><tr><th>Item</th><th>Hours</th><th>Rate</th><th>Amount</th></tr></thead>\n",
+    );
+    out.push_str("    <tbody>\n");
+    for (label, line) in &earnings.lines {
+        out.push_str(&format!(
+            "      <tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            escape_html(label),
+            hours(line.rounded),
+            line.rate
+                .map_or("mixed".to_string(), |rate| money(rate, precision)),
+            money(line.amount, precision)
+        ));
+    }
+    out.push_str("    </tbody>\n");
+    out.push_str(&format!(
+        "    <tfoot><tr><th>Total</th><th class=\"num\">{}</th><th></th><th class=\"num\">{} {}</th></tr></tfoot>\n",
+        hours(billed_duration(earnings)),
+        money(invoice.total, precision),
+        escape_html(&invoice.currency)
+    ));
+    out.push_str("  </table>\n");
+    out.push_str("</div>\n");
+    out
+}
+
+// 独立的 HTML 页面，适合打印或在浏览器中另存为 PDF
+fn render_print(invoice: &Invoice, earnings: &Earnings, precision: u32) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Invoice {number}</title>
+<style>
+  @page {{ size: A4; margin: 20mm; }}
+  body {{ font-family: -apple-system, "Segoe UI", "PingFang SC", "Microsoft YaHei", sans-serif; color: #222; max-width: 800px; margin: 40px auto; }}
+  h1 {{ font-size: 28px; margin-bottom: 24px; }}
+  dl {{ display: grid; grid-template-columns: 120px auto; row-gap: 4px; margin-bottom: 32px; }}
+  dt {{ color: #666; }}
+  dd {{ margin: 0; }}
+  table {{ width: 100%; border-collapse: collapse; }}
+  th, td {{ padding: 8px; border-bottom: 1px solid #ddd; text-align: left; }}
+  tfoot th {{ border-top: 2px solid #222; border-bottom: none; }}
+  .num {{ text-align: right; }}
+  @media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        number = escape_html(&invoice.number),
+        body = render_html(invoice, earnings, precision)
+    )
+}
@@ -1,5 +1,26 @@
 mod app;
+mod budget;
+mod chart;
+#[allow(clippy::module_inception)]
 pub mod cli;
 mod clocker;
+mod completion;
+mod config;
 mod display;
+mod goal;
+mod invoice;
+mod picker;
+mod project;
+mod rate;
+mod record;
+mod render;
+mod report;
+mod search;
+mod standup;
+mod status;
+mod table;
+mod tag;
+mod template;
+mod time_parser;
 mod utils;
+mod workspace;
@@ -0,0 +1,37 @@
+use super::app::AppHandle;
+use anyhow::anyhow;
+use dialoguer::{FuzzySelect, theme::ColorfulTheme};
+use std::io::IsTerminal;
+
+// 参与排序的最近记录数
+const RECENT_RECORDS: usize = 1000;
+// 选择列表中最多展示的描述数
+const MAX_ITEMS: usize = 50;
+const EMPTY_ITEM: &str = "(no description)";
+
+// s 不带参数时从最近用过的描述中模糊搜索选择，选择第一项或无法交互时开始没有描述的计时器
+pub fn pick_recent_description(app_handle: &AppHandle) -> anyhow::Result<Option<String>> {
+    // 已有计时器在运行时无需选择，开始计时会提示计时器正在运行
+    if !std::io::stdin().is_terminal() || app_handle.get_open_time_slice()?.is_some() {
+        return Ok(None);
+    }
+    let recent = app_handle.get_recent_descriptions(RECENT_RECORDS)?;
+    if recent.is_empty() {
+        return Ok(None);
+    }
+    let items: Vec<&str> = std::iter::once(EMPTY_ITEM)
+        .chain(
+            recent
+                .iter()
+                .take(MAX_ITEMS)
+                .map(|recent| recent.desc.as_str()),
+        )
+        .collect();
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Description")
+        .items(&items)
+        .default(1)
+        .interact_opt()?
+        .ok_or_else(|| anyhow!("No timer started"))?;
+    Ok((selection > 0).then(|| items[selection].to_string()))
+}
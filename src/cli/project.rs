@@ -0,0 +1,156 @@
+use super::app::AppHandle;
+use super::display::{display_clients, display_project_totals, display_projects};
+use super::render::OutputArgs;
+use crate::core::timeline::TimelineFilter;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum ProjectCommands {
+    /// Create a project, optionally owned by a client
+    Add {
+        name: String,
+        #[arg(short, long)]
+        client: Option<String>,
+    },
+    /// List projects
+    #[clap(alias = "ls")]
+    List {
+        /// Include archived projects
+        #[arg(short, long)]
+        all: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    /// Assign the project to a client, or detach it when no client is given
+    Client {
+        name: String,
+        client: Option<String>,
+    },
+    /// Archived projects are hidden and can't be started
+    Archive {
+        name: String,
+    },
+    Unarchive {
+        name: String,
+    },
+    /// Remove a project, its time records are kept
+    #[clap(alias = "rm")]
+    Remove {
+        name: String,
+    },
+    /// Show total time per project grouped by client
+    Totals {
+        /// Include archived projects
+        #[arg(short, long)]
+        all: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ClientCommands {
+    Add {
+        name: String,
+    },
+    /// List clients
+    #[clap(alias = "ls")]
+    List {
+        /// Include archived clients
+        #[arg(short, long)]
+        all: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    Archive {
+        name: String,
+    },
+    Unarchive {
+        name: String,
+    },
+    /// Remove a client, its projects are kept without a client
+    #[clap(alias = "rm")]
+    Remove {
+        name: String,
+    },
+}
+
+pub fn handle_project_command(app_handle: &AppHandle, command: ProjectCommands) {
+    let result = match command {
+        ProjectCommands::Add { name, client } => app_handle
+            .add_project(&name, client.as_deref())
+            .map(|project| println!("Project '{}' created.", project.name)),
+        ProjectCommands::List { all, output } => app_handle.get_projects().map(|projects| {
+            let projects: Vec<_> = projects
+                .into_iter()
+                .filter(|p| all || !p.archived)
+                .collect();
+            display_projects(&projects, output.format);
+        }),
+        ProjectCommands::Rename { name, new_name } => app_handle
+            .rename_project(&name, &new_name)
+            .map(|_| println!("Project '{name}' renamed to '{new_name}'.")),
+        ProjectCommands::Client { name, client } => app_handle
+            .set_project_client(&name, client.as_deref())
+            .map(|_| match client {
+                Some(client) => println!("Project '{name}' assigned to client '{client}'."),
+                None => println!("Project '{name}' detached from its client."),
+            }),
+        ProjectCommands::Archive { name } => app_handle
+            .set_project_archived(&name, true)
+            .map(|_| println!("Project '{name}' archived.")),
+        ProjectCommands::Unarchive { name } => app_handle
+            .set_project_archived(&name, false)
+            .map(|_| println!("Project '{name}' unarchived.")),
+        ProjectCommands::Remove { name } => app_handle
+            .remove_project(&name)
+            .map(|_| println!("Project '{name}' removed.")),
+        ProjectCommands::Totals { all, output } => app_handle.get_projects().and_then(|projects| {
+            let projects: Vec<_> = projects
+                .into_iter()
+                .filter(|p| all || !p.archived)
+                .collect();
+            let timeline = app_handle.get_timeline(&TimelineFilter::default())?;
+            display_project_totals(&projects, &timeline.project_totals(), output.format);
+            Ok(())
+        }),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+pub fn handle_client_command(app_handle: &AppHandle, command: ClientCommands) {
+    let result = match command {
+        ClientCommands::Add { name } => app_handle
+            .add_client(&name)
+            .map(|client| println!("Client '{}' created.", client.name)),
+        ClientCommands::List { all, output } => app_handle.get_clients().map(|clients| {
+            let clients: Vec<_> = clients.into_iter().filter(|c| all || !c.archived).collect();
+            display_clients(&clients, output.format);
+        }),
+        ClientCommands::Rename { name, new_name } => app_handle
+            .rename_client(&name, &new_name)
+            .map(|_| println!("Client '{name}' renamed to '{new_name}'.")),
+        ClientCommands::Archive { name } => app_handle
+            .set_client_archived(&name, true)
+            .map(|_| println!("Client '{name}' archived.")),
+        ClientCommands::Unarchive { name } => app_handle
+            .set_client_archived(&name, false)
+            .map(|_| println!("Client '{name}' unarchived.")),
+        ClientCommands::Remove { name } => app_handle
+            .remove_client(&name)
+            .map(|_| println!("Client '{name}' removed.")),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
@@ -0,0 +1,51 @@
+use super::app::AppHandle;
+use super::display::display_rates;
+use super::render::OutputArgs;
+use super::utils::parse_date;
+use crate::core::rate::RateTarget;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum RateCommands {
+    /// Set the hourly rate of a project, client or tag
+    Set {
+        /// One of "project", "client" or "tag"
+        target: RateTarget,
+        name: String,
+        hourly_rate: f64,
+        /// Date the rate takes effect (e.g. "2025-01-01"), defaults to always
+        #[arg(short, long, value_parser = parse_date)]
+        from: Option<u64>,
+    },
+    /// List all rates
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Remove a rate by its ID
+    #[clap(alias = "rm")]
+    Remove { id: u64 },
+}
+
+pub fn handle_rate_command(app_handle: &AppHandle, command: RateCommands) {
+    let result = match command {
+        RateCommands::Set {
+            target,
+            name,
+            hourly_rate,
+            from,
+        } => app_handle
+            .set_rate(target, &name, hourly_rate, from.unwrap_or(0))
+            .map(|_| println!("Rate of {target} '{name}' set to {hourly_rate}/h.")),
+        RateCommands::List { output } => app_handle
+            .get_rates()
+            .map(|rates| display_rates(&rates, output.format)),
+        RateCommands::Remove { id } => app_handle
+            .remove_rate(id)
+            .map(|_| println!("Rate {id} removed.")),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
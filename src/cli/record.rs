@@ -0,0 +1,73 @@
+use super::app::AppHandle;
+use super::time_parser::{parse_time, parse_time_on};
+use super::utils::{DurationError, get_datetime, get_datetime_str, parse_duration};
+use anyhow::anyhow;
+
+// 补录时间记录，end 可以是时间点（例如 "15:30"）也可以是时长（例如 "1h30m"）
+pub fn add_record(
+    app_handle: &AppHandle,
+    start: &str,
+    end: &str,
+    desc: Vec<String>,
+) -> anyhow::Result<()> {
+    let start_time = parse_time(start).map_err(anyhow::Error::msg)?;
+    let end_time =
+        match parse_duration(end) {
+            Ok(duration) => start_time
+                .checked_add(duration)
+                .ok_or_else(|| anyhow!("Duration is too long: '{end}'"))?,
+            // 既不是时长也不是时间点时，过长的时长比无效的时间更能说明问题
+            Err(duration_error) => parse_time_on(end, get_datetime(start_time).date_naive())
+                .map_err(|time_error| match duration_error {
+                    DurationError::TooLong(_) => anyhow!(duration_error),
+                    DurationError::Invalid(_) => anyhow::Error::msg(time_error),
+                })?,
+        };
+    let desc = if desc.is_empty() {
+        None
+    } else {
+        Some(desc.join(" "))
+    };
+    let id = app_handle.add_time_slice(start_time, end_time, desc)?;
+    println!(
+        "Time record {id} added: {} - {}",
+        get_datetime_str(start_time),
+        get_datetime_str(end_time)
+    );
+    Ok(())
+}
+
+// 修改时间记录，只有时刻的时间（例如 "14:30"）落在记录开始的那一天
+pub fn edit_record(
+    app_handle: &AppHandle,
+    id: u64,
+    start: Option<String>,
+    end: Option<String>,
+    duration: Option<u64>,
+    desc: Option<String>,
+) -> anyhow::Result<()> {
+    if start.is_none() && end.is_none() && duration.is_none() && desc.is_none() {
+        return Err(anyhow!(
+            "Nothing to edit, use --start, --end, --duration or --desc"
+        ));
+    }
+    let time_slice = app_handle.get_time_slice(id)?;
+    let day = get_datetime(time_slice.start_time).date_naive();
+    let start_time = start
+        .map(|start| parse_time_on(&start, day))
+        .transpose()
+        .map_err(anyhow::Error::msg)?;
+    let end_time = match (end, duration) {
+        (Some(end), _) => Some(parse_time_on(&end, day).map_err(anyhow::Error::msg)?),
+        (None, Some(duration)) => Some(
+            start_time
+                .unwrap_or(time_slice.start_time)
+                .checked_add(duration)
+                .ok_or_else(|| anyhow!("Duration is too long"))?,
+        ),
+        (None, None) => None,
+    };
+    app_handle.edit_time_slice(id, start_time, end_time, desc.as_deref())?;
+    println!("Time record {id} updated.");
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use super::table::{Table, strip_ansi};
+use clap::{Args, ValueEnum};
+
+// 列表与报表的输出格式
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Aligned table for the terminal
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl OutputFormat {
+    // width 为终端表格可以使用的列数
+    pub fn renderer(&self, width: usize) -> Box<dyn Renderer> {
+        match self {
+            OutputFormat::Table => Box::new(TextRenderer { width }),
+            OutputFormat::Json => Box::new(JsonRenderer),
+            OutputFormat::Csv => Box::new(CsvRenderer),
+            OutputFormat::Markdown => Box::new(MarkdownRenderer),
+            OutputFormat::Html => Box::new(HtmlRenderer),
+        }
+    }
+
+    // 是否为终端中阅读的格式，其它格式只输出表格本身，方便交给其它工具处理
+    pub fn is_table(&self) -> bool {
+        *self == OutputFormat::Table
+    }
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+pub struct OutputArgs {
+    /// Output format: table, json, csv, markdown or html
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+// 将表格渲染为某种格式的文本
+pub trait Renderer {
+    fn render(&self, table: &Table) -> String;
+}
+
+pub struct TextRenderer {
+    pub width: usize,
+}
+
+impl Renderer for TextRenderer {
+    fn render(&self, table: &Table) -> String {
+        table.lines(self.width).join("\n")
+    }
+}
+
+// JSON 中的字段名，例如 "Rate/h" -> "rate_h"
+fn json_key(header: &str) -> String {
+    header
+        .to_lowercase()
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("_")
+}
+
+// 每一行为一个对象的数组，汇总行不输出，数字列输出为数字，空白输出为 null
+pub struct JsonRenderer;
+
+fn json_value(cell: String, numeric: bool) -> serde_json::Value {
+    if cell.is_empty() {
+        return serde_json::Value::Null;
+    }
+    if numeric {
+        if let Ok(number) = cell.parse::<u64>() {
+            return number.into();
+        }
+        if let Ok(number) = cell.parse::<f64>() {
+            return number.into();
+        }
+    }
+    cell.into()
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, table: &Table) -> String {
+        let keys: Vec<String> = table.headers().into_iter().map(json_key).collect();
+        let rows: Vec<serde_json::Value> = table
+            .rows()
+            .iter()
+            .map(|row| {
+                let object = keys
+                    .iter()
+                    .zip(row)
+                    .enumerate()
+                    .map(|(ind, (key, cell))| {
+                        let value = json_value(strip_ansi(cell), table.is_numeric(ind));
+                        (key.clone(), value)
+                    })
+                    .collect();
+                serde_json::Value::Object(object)
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap_or_default()
+    }
+}
+
+// 含有逗号、引号或换行的字段加上引号
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// 第一行为表头，汇总行不输出
+pub struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn render(&self, table: &Table) -> String {
+        let line = |cells: Vec<String>| {
+            cells
+                .iter()
+                .map(|cell| escape_csv(cell))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        let mut out = vec![line(
+            table.headers().into_iter().map(str::to_string).collect(),
+        )];
+        for row in table.rows() {
+            out.push(line(row.iter().map(|cell| strip_ansi(cell)).collect()));
+        }
+        out.join("\n")
+    }
+}
+
+fn escape_markdown(s: &str) -> String {
+    strip_ansi(s).replace('|', "\\|").replace('\n', "<br>")
+}
+
+// 汇总行以粗体追加在表格末尾
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, table: &Table) -> String {
+        let headers = table.headers();
+        let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let mut out = vec![
+            format!("### {}", table.title()),
+            String::new(),
+            line(
+                headers
+                    .iter()
+                    .map(|header| escape_markdown(header))
+                    .collect(),
+            ),
+            line(headers.iter().map(|_| "---".to_string()).collect()),
+        ];
+        for row in table.rows() {
+            out.push(line(
+                (0..headers.len())
+                    .map(|ind| {
+                        row.get(ind)
+                            .map_or(String::new(), |cell| escape_markdown(cell))
+                    })
+                    .collect(),
+            ));
+        }
+        for row in table.footer_rows() {
+            out.push(line(
+                (0..headers.len())
+                    .map(|ind| match row.get(ind).map(|cell| escape_markdown(cell)) {
+                        Some(cell) if !cell.is_empty() => format!("**{cell}**"),
+                        _ => String::new(),
+                    })
+                    .collect(),
+            ));
+        }
+        out.join("\n")
+    }
+}
+
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// 汇总行放在 tfoot 中
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, table: &Table) -> String {
+        let cells = |tag: &str, cells: &[String]| {
+            cells
+                .iter()
+                .map(|cell| {
+                    let cell = escape_html(&strip_ansi(cell)).replace('\n', "<br>");
+                    format!("<{tag}>{cell}</{tag}>")
+                })
+                .collect::<String>()
+        };
+        let headers: Vec<String> = table.headers().into_iter().map(str::to_string).collect();
+        let mut out = String::new();
+        out.push_str("<table>\n");
+        out.push_str(&format!(
+            "  <caption>{}</caption>\n",
+            escape_html(table.title())
+        ));
+        out.push_str(&format!(
+            "  <thead><tr>{}</tr></thead>\n",
+            cells("th", &headers)
+        ));
+        out.push_str("  <tbody>\n");
+        for row in table.rows() {
+            out.push_str(&format!("    <tr>{}</tr>\n", cells("td", row)));
+        }
+        out.push_str("  </tbody>\n");
+        if !table.footer_rows().is_empty() {
+            out.push_str("  <tfoot>\n");
+            for row in table.footer_rows() {
+                out.push_str(&format!("    <tr>{}</tr>\n", cells("th", row)));
+            }
+            out.push_str("  </tfoot>\n");
+        }
+        out.push_str("</table>");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_only_numeric_columns_become_numbers() {
+        let mut table = Table::new("Search", &["ID", "Rate/h", "Description"])
+            .numeric(0)
+            .numeric(1);
+        table.row(vec!["12".to_string(), "97.5".to_string(), "42".to_string()]);
+        table.row(vec![
+            "13".to_string(),
+            String::new(),
+            "\x1b[1mfix\x1b[0m".to_string(),
+        ]);
+        let json: serde_json::Value =
+            serde_json::from_str(&JsonRenderer.render(&table)).expect("valid JSON");
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"id": 12, "rate_h": 97.5, "description": "42"},
+                {"id": 13, "rate_h": null, "description": "fix"},
+            ])
+        );
+    }
+
+    #[test]
+    fn csv_quotes_special_characters() {
+        let mut table = Table::new("Tags", &["Tag", "Note"]);
+        table.row(vec!["a,b".to_string(), "say \"hi\"".to_string()]);
+        assert_eq!(
+            CsvRenderer.render(&table),
+            "Tag,Note\n\"a,b\",\"say \"\"hi\"\"\""
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use super::app::AppHandle;
+use super::config::{calendar, config};
+use super::display::format_duration;
+use super::utils::{check_time_format, format_date, get_current_time, get_datetime};
+use crate::core::{report::ReportData, timeline::TimelineFilter};
+use anyhow::anyhow;
+use chrono::{NaiveDate, NaiveTime};
+use minijinja::{Environment, Error, ErrorKind, Value};
+use std::fmt::{Display, Write as _};
+use std::io::Write;
+use std::path::Path;
+
+// 内置的模板，--template 不是文件路径时按名称查找
+const BUILTIN_TEMPLATES: [(&str, &str); 2] = [
+    ("standup", include_str!("templates/standup.md")),
+    ("weekly", include_str!("templates/weekly.md")),
+];
+
+// 读取模板文件，文件不存在时使用同名的内置模板
+fn load_template(name: &str) -> anyhow::Result<String> {
+    if Path::new(name).is_file() {
+        return Ok(std::fs::read_to_string(name)?);
+    }
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, source)| source.to_string())
+        .ok_or_else(|| {
+            anyhow!(
+                "Template not found: '{name}'. Use a file path or one of the built-in templates: {}",
+                BUILTIN_TEMPLATES.map(|(builtin, _)| builtin).join(", ")
+            )
+        })
+}
+
+// 毫秒时长，例如 {{ total | duration }} -> 01:30:00
+fn duration_filter(ms: u64) -> String {
+    format_duration(ms)
+}
+
+// 以小时为单位的时长，例如 {{ total | hours }} -> 1.50
+fn hours_filter(ms: u64, precision: Option<usize>) -> String {
+    format!("{:.*}", precision.unwrap_or(2), ms as f64 / 3_600_000.0)
+}
+
+// 毫秒时间戳或 YYYY-MM-DD 格式的日期，默认使用配置中的 format.date
+fn date_filter(value: Value, format: Option<String>) -> Result<String, Error> {
+    let date = match value.as_str() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!("Invalid date: '{date}'"),
+            )
+        })?,
+        None => get_datetime(u64::try_from(value)?).date_naive(),
+    };
+    match format {
+        Some(format) => {
+            check_format(&format)?;
+            write_formatted(date.and_time(NaiveTime::MIN).format(&format), &format)
+        }
+        None => Ok(format_date(date)),
+    }
+}
+
+// 毫秒时间戳的时刻，默认使用配置中的 format.time
+fn time_filter(ms: u64, format: Option<String>) -> Result<String, Error> {
+    let format = format.unwrap_or_else(|| config().format.time);
+    check_format(&format)?;
+    write_formatted(get_datetime(ms).format(&format), &format)
+}
+
+// 模板中给出的格式字符串无效时返回模板错误，而不是在格式化时让程序崩溃
+fn check_format(format: &str) -> Result<(), Error> {
+    check_time_format(format).map_err(|e| Error::new(ErrorKind::InvalidOperation, e))
+}
+
+// 格式中有不适用于该值的字段时（例如日期中的 %z）同样返回模板错误
+fn write_formatted(formatted: impl Display, format: &str) -> Result<String, Error> {
+    let mut output = String::new();
+    write!(output, "{formatted}").map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("Format '{format}' does not apply to this value"),
+        )
+    })?;
+    Ok(output)
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.add_filter("duration", duration_filter);
+    env.add_filter("hours", hours_filter);
+    env.add_filter("date", date_filter);
+    env.add_filter("time", time_filter);
+    env
+}
+
+fn render_report(
+    app_handle: &AppHandle,
+    filter: &TimelineFilter,
+    template: &str,
+) -> anyhow::Result<String> {
+    let source = load_template(template)?;
+    let timeline = app_handle.get_timeline(filter)?;
+    let data = ReportData::new(
+        &timeline,
+        &calendar(),
+        filter.start_time,
+        filter.end_time,
+        get_current_time(),
+    );
+    let env = environment();
+    let output = env
+        .template_from_named_str(template, &source)?
+        .render(&data)
+        .map_err(|e| anyhow!("{e:#}"))?;
+    Ok(output.trim_end().to_string())
+}
+
+// 用模板渲染筛选后的记录，template 为模板文件路径或内置模板的名称
+pub fn show_template_report(app_handle: &AppHandle, filter: &TimelineFilter, template: &str) {
+    match render_report(app_handle, filter, template) {
+        // 输出可能被管道截断，忽略写入错误
+        Ok(output) => {
+            let _ = writeln!(std::io::stdout(), "{output}");
+        }
+        Err(e) => println!("{}", e),
+    }
+}
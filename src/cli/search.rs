@@ -0,0 +1,32 @@
+use super::app::AppHandle;
+use super::display::display_search_results;
+use super::render::OutputFormat;
+use crate::core::timeline::TimelineFilter;
+
+// 搜索描述，只保留符合筛选条件的记录，结果按相关度排序
+pub fn search(
+    app_handle: &AppHandle,
+    query: &str,
+    filter: &TimelineFilter,
+    limit: usize,
+    format: OutputFormat,
+) {
+    let result = app_handle.search_descriptions(query).and_then(|hits| {
+        let timeline = app_handle.get_timeline(filter)?;
+        let hits = hits
+            .into_iter()
+            .filter(|hit| {
+                timeline
+                    .list
+                    .iter()
+                    .any(|slice| slice.id == hit.time_slice_id)
+            })
+            .take(limit)
+            .collect::<Vec<_>>();
+        display_search_results(&timeline, &hits, format);
+        Ok(())
+    });
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
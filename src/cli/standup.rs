@@ -0,0 +1,46 @@
+use super::app::AppHandle;
+use super::config::{calendar, config};
+use super::display::display_standup;
+use super::render::OutputArgs;
+use super::utils::{parse_date, parse_tag_filter};
+use crate::core::{
+    standup::{previous_working_day, standup_items},
+    timeline::TimelineFilter,
+};
+use chrono::Duration;
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct StandupArgs {
+    /// Summarize this day instead of the previous working day (e.g. "2025-02-13", "last friday")
+    #[arg(long, value_parser = parse_date)]
+    date: Option<u64>,
+    /// Only include records within the tag subtree (e.g. "client/acme")
+    #[arg(short, long)]
+    tag: Vec<String>,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+// 汇总上一个工作日（按配置跳过非工作日）的记录，合并相同的任务
+pub fn show_standup(app_handle: &AppHandle, args: &StandupArgs) {
+    let calendar = calendar();
+    let date = match args.date {
+        Some(time) => calendar.date_of(time),
+        None => previous_working_day(calendar.today(), &config().working_days()),
+    };
+    let filter = TimelineFilter {
+        tags: parse_tag_filter(&args.tag),
+        start_time: Some(calendar.day_start(date)),
+        end_time: Some(calendar.day_start(date + Duration::days(1))),
+        ..Default::default()
+    };
+    match app_handle.get_timeline(&filter) {
+        Ok(timeline) => display_standup(
+            date,
+            &standup_items(&timeline, date, &calendar),
+            args.output.format,
+        ),
+        Err(e) => println!("{}", e),
+    }
+}
@@ -0,0 +1,148 @@
+use super::display::format_duration;
+use super::utils::{get_current_time, get_time_str, parse_project, parse_tags};
+use crate::core::time_slice::OpenTimeSlice;
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Output template, placeholders: {desc} {tags} {project} {start} {elapsed} {remaining}
+    #[arg(short, long, default_value = "{elapsed} {desc}")]
+    format: String,
+    /// Text printed when no timer is running
+    #[arg(long, default_value = "")]
+    idle: String,
+    /// Print JSON for waybar custom modules (text, tooltip, class, percentage)
+    #[arg(long)]
+    waybar: bool,
+}
+
+// 正在运行的计时器，预定结束时间已过的计时器视为已停止
+fn running_slice(open: Option<OpenTimeSlice>, now: u64) -> Option<OpenTimeSlice> {
+    open.filter(|open| open.planned_end.is_none_or(|planned_end| planned_end > now))
+}
+
+// 占位符对应的计时器信息，不认识的占位符返回 None
+fn placeholder(name: &str, open: &OpenTimeSlice, now: u64) -> Option<String> {
+    let desc = open.desc.as_deref().unwrap_or_default().trim();
+    let value = match name {
+        "desc" => desc.to_string(),
+        "tags" => parse_tags(desc)
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<String>>()
+            .join(" "),
+        "project" => parse_project(desc)
+            .map(|project| format!("@{project}"))
+            .unwrap_or_default(),
+        "start" => get_time_str(open.time_slice.start_time),
+        "elapsed" => format_duration(now.saturating_sub(open.time_slice.start_time)),
+        "remaining" => open
+            .planned_end
+            .map(|planned_end| format_duration(planned_end.saturating_sub(now)))
+            .unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// 将模板中的占位符替换为计时器的信息，只扫描一遍模板，描述中的 {...} 原样输出
+fn render(template: &str, open: &OpenTimeSlice, now: u64) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let replaced = tail
+            .find('}')
+            .and_then(|end| placeholder(&tail[1..end], open, now).map(|value| (end, value)));
+        match replaced {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+// 输出当前计时器的状态，供 shell 提示符、tmux 状态栏与 waybar 等使用
+pub fn show_status(open: anyhow::Result<Option<OpenTimeSlice>>, args: &StatusArgs) {
+    let now = get_current_time();
+    let open = match open {
+        Ok(open) => running_slice(open, now),
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let text = open
+        .as_ref()
+        .map_or(args.idle.clone(), |open| render(&args.format, open, now));
+    if !args.waybar {
+        println!("{text}");
+        return;
+    }
+    // waybar 的 custom 模块读取单行 JSON
+    let json = match &open {
+        Some(open) => {
+            let mut json = serde_json::json!({
+                "text": text,
+                "tooltip": render("{desc}\nStarted at {start}, {elapsed} elapsed", open, now),
+                "class": "running",
+            });
+            if let Some(planned_end) = open.planned_end {
+                let total = planned_end
+                    .saturating_sub(open.time_slice.start_time)
+                    .max(1);
+                let elapsed = now.saturating_sub(open.time_slice.start_time);
+                json["percentage"] = (elapsed * 100 / total).min(100).into();
+            }
+            json
+        }
+        None => serde_json::json!({
+            "text": text,
+            "tooltip": "No timer is running",
+            "class": "idle",
+        }),
+    };
+    println!("{json}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time_slice::TimeSlice;
+
+    fn open(desc: &str) -> OpenTimeSlice {
+        OpenTimeSlice {
+            time_slice: TimeSlice::new(1, 0, None),
+            desc: Some(desc.to_string()),
+            planned_end: Some(30 * 60 * 1000),
+        }
+    }
+
+    #[test]
+    fn render_placeholders() {
+        let open = open("fix login #code/backend @acme");
+        let now = 90 * 1000;
+        assert_eq!(
+            render("{elapsed} {tags} {project} {remaining}", &open, now),
+            "00:01:30 #code/backend @acme 00:28:30"
+        );
+        assert_eq!(render("{unknown} {desc", &open, now), "{unknown} {desc");
+    }
+
+    #[test]
+    fn render_keeps_placeholders_in_description() {
+        let open = open("write {elapsed} and {tags} docs");
+        assert_eq!(
+            render("[{desc}] {elapsed}", &open, 0),
+            "[write {elapsed} and {tags} docs] 00:00:00"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use super::display::terminal_width;
+use super::render::OutputFormat;
+use std::io::Write;
+use unicode_width::UnicodeWidthChar;
+
+// 列之间的空白
+const GAP: &str = "  ";
+// 终端过窄时可收缩的列最少保留的宽度
+const MIN_WIDTH: usize = 8;
+// 收缩其它列之前折行的列至少保留的宽度
+const WRAP_MIN_WIDTH: usize = 24;
+
+// 终端中的显示宽度，中文等全角字符占两列，颜色控制序列不占宽度
+pub fn display_width(s: &str) -> usize {
+    segments(s)
+        .map(|(text, ch)| if text { ch.width().unwrap_or(0) } else { 0 })
+        .sum()
+}
+
+// 去掉颜色控制序列，用于导出等不在终端中展示的格式
+pub fn strip_ansi(s: &str) -> String {
+    segments(s)
+        .filter(|(visible, _)| *visible)
+        .map(|(_, ch)| ch)
+        .collect()
+}
+
+// 将字符串拆分为可见字符与颜色控制序列中的字符，true 表示可见字符
+fn segments(s: &str) -> impl Iterator<Item = (bool, char)> + '_ {
+    let mut in_escape = false;
+    s.chars().map(move |ch| {
+        if ch == '\x1b' {
+            in_escape = true;
+        }
+        let visible = !in_escape;
+        if in_escape && ch == 'm' {
+            in_escape = false;
+        }
+        (visible, ch)
+    })
+}
+
+// 在右侧补空格到 width 列，超出时保持原样
+pub fn pad(s: &str, width: usize) -> String {
+    format!("{s}{}", " ".repeat(width.saturating_sub(display_width(s))))
+}
+
+// 截断到 width 列以内，截断处以省略号结尾
+pub fn truncate(s: &str, width: usize) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    let mut colored = false;
+    for (visible, ch) in segments(s) {
+        if !visible {
+            colored = true;
+            out.push(ch);
+            continue;
+        }
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        used += ch_width;
+        out.push(ch);
+    }
+    out.push('…');
+    if colored {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+// 按 width 列折行，优先在空白处断开，中文等没有空白的文字按字符断开
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut used = 0;
+    // 当前行中最后一个空白的位置与此前的宽度
+    let mut last_space: Option<(usize, usize)> = None;
+    for (visible, ch) in segments(s) {
+        if !visible {
+            line.push(ch);
+            continue;
+        }
+        let ch_width = ch.width().unwrap_or(0);
+        // 比列宽还宽的字符（例如宽度为 1 时的中文）单独占一行，不在前面留下空行
+        if used > 0 && used + ch_width > width {
+            match last_space.take() {
+                // 空白之后的部分过长时（例如一长串中文）直接按字符断开，避免留下大段空白
+                Some((pos, before)) if ch != ' ' && used - before <= width / 2 => {
+                    let rest = line.split_off(pos);
+                    lines.push(line.trim_end().to_string());
+                    line = rest.trim_start().to_string();
+                    used -= before + 1;
+                }
+                _ => {
+                    lines.push(line.trim_end().to_string());
+                    line = String::new();
+                    used = 0;
+                }
+            }
+            if ch == ' ' {
+                continue;
+            }
+        }
+        if ch == ' ' {
+            last_space = Some((line.len(), used));
+        }
+        line.push(ch);
+        used += ch_width;
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    // 颜色可能跨行，每行末尾恢复默认颜色
+    if s.contains('\x1b') {
+        for line in &mut lines {
+            line.push_str("\x1b[0m");
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    // 超出宽度时截断并以省略号结尾
+    Truncate,
+    // 超出宽度时折成多行
+    Wrap,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    header: String,
+    max_width: Option<usize>,
+    overflow: Overflow,
+    // 终端过窄时是否可以收缩
+    shrink: bool,
+    // JSON 中输出为数字
+    numeric: bool,
+}
+
+// 按显示宽度对齐的表格，列宽由内容决定，超出终端宽度时收缩可收缩的列
+#[derive(Debug, Clone)]
+pub struct Table {
+    title: String,
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    // 表格下方的汇总行，不参与列宽计算
+    footer: Vec<Vec<String>>,
+    show_header: bool,
+}
+
+impl Table {
+    pub fn new(title: &str, headers: &[&str]) -> Self {
+        Table {
+            title: title.to_string(),
+            columns: headers
+                .iter()
+                .map(|header| Column {
+                    header: header.to_string(),
+                    max_width: None,
+                    overflow: Overflow::Truncate,
+                    shrink: false,
+                    numeric: false,
+                })
+                .collect(),
+            rows: vec![],
+            footer: vec![],
+            show_header: true,
+        }
+    }
+
+    // 列宽不超过 width，并在终端过窄时允许收缩
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        self.columns[column].max_width = Some(width);
+        self.columns[column].shrink = true;
+        self
+    }
+
+    // 内容过长时折行，终端过窄时优先收缩这一列
+    pub fn wrap(mut self, column: usize) -> Self {
+        self.columns[column].overflow = Overflow::Wrap;
+        self.columns[column].shrink = true;
+        self
+    }
+
+    // 数字列，例如 ID，其它列即使内容像数字也按文字输出
+    pub fn numeric(mut self, column: usize) -> Self {
+        self.columns[column].numeric = true;
+        self
+    }
+
+    pub fn hide_header(mut self) -> Self {
+        self.show_header = false;
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn footer(&mut self, cells: Vec<String>) {
+        self.footer.push(cells);
+    }
+
+    // 按 max_width 列以内计算每列的宽度
+    fn widths(&self, max_width: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(ind, column)| {
+                let header = if self.show_header {
+                    display_width(&column.header)
+                } else {
+                    0
+                };
+                let content = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(ind))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0);
+                let width = header.max(content);
+                column
+                    .max_width
+                    .map_or(width, |max| width.min(max.max(header)))
+            })
+            .collect();
+        let gaps = GAP.len() * widths.len().saturating_sub(1);
+        // 先把折行的列收缩到 WRAP_MIN_WIDTH，再收缩最宽的可收缩列，最后继续收缩折行的列
+        let is_wrap = |ind: usize| self.columns[ind].overflow == Overflow::Wrap;
+        while widths.iter().sum::<usize>() + gaps > max_width {
+            let Some(ind) = (0..widths.len())
+                .filter(|&ind| is_wrap(ind) && widths[ind] > WRAP_MIN_WIDTH)
+                .chain(
+                    (0..widths.len())
+                        .filter(|&ind| self.columns[ind].shrink && widths[ind] > MIN_WIDTH)
+                        .max_by_key(|&ind| (!is_wrap(ind), widths[ind])),
+                )
+                .next()
+            else {
+                break;
+            };
+            widths[ind] -= 1;
+        }
+        widths
+    }
+
+    fn render_row(&self, cells: &[String], widths: &[usize], out: &mut Vec<String>) {
+        let columns: Vec<Vec<String>> = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(ind, (column, &width))| {
+                let cell = cells.get(ind).map_or("", String::as_str);
+                match column.overflow {
+                    Overflow::Truncate => vec![truncate(cell, width)],
+                    Overflow::Wrap => wrap(cell, width),
+                }
+            })
+            .collect();
+        let height = columns.iter().map(Vec::len).max().unwrap_or(1);
+        for line in 0..height {
+            let text = columns
+                .iter()
+                .zip(widths)
+                .map(|(lines, &width)| pad(lines.get(line).map_or("", String::as_str), width))
+                .collect::<Vec<String>>()
+                .join(GAP);
+            out.push(text.trim_end().to_string());
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn headers(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.header.as_str()).collect()
+    }
+
+    pub fn is_numeric(&self, column: usize) -> bool {
+        self.columns.get(column).is_some_and(|c| c.numeric)
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    pub fn footer_rows(&self) -> &[Vec<String>] {
+        &self.footer
+    }
+
+    // 按 width 列排版为多行文本
+    pub fn lines(&self, width: usize) -> Vec<String> {
+        let widths = self.widths(width);
+        let table_width = (widths.iter().sum::<usize>()
+            + GAP.len() * widths.len().saturating_sub(1))
+        .max(display_width(&self.title) + 12);
+        let mut out = vec![format!("{:-<table_width$}", format!("----{}", self.title))];
+        if self.show_header {
+            let headers: Vec<String> = self.columns.iter().map(|c| c.header.clone()).collect();
+            self.render_row(&headers, &widths, &mut out);
+        }
+        for row in &self.rows {
+            self.render_row(row, &widths, &mut out);
+        }
+        out.push("-".repeat(table_width));
+        // 汇总行的内容从所在列的开始位置写起，放不下时顺延
+        for row in &self.footer {
+            let mut text = String::new();
+            let mut offset = 0;
+            for (cell, width) in row.iter().zip(&widths) {
+                if !cell.is_empty() {
+                    let used = display_width(&text);
+                    text = if text.is_empty() || used + GAP.len() <= offset {
+                        pad(&text, offset)
+                    } else {
+                        format!("{text}{GAP}")
+                    };
+                    text.push_str(cell);
+                }
+                offset += width + GAP.len();
+            }
+            out.push(text);
+        }
+        out
+    }
+
+    // 按指定的格式输出，终端表格按终端宽度排版
+    // 输出交给 head 等工具时管道可能提前关闭，忽略写入错误
+    pub fn print(&self, format: OutputFormat) {
+        let output = format.renderer(terminal_width()).render(self);
+        let _ = writeln!(std::io::stdout(), "{}", output.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 标签树中只有叶子带颜色，例如 "  \x1b[31mbackend\x1b[0m"
+    const PAINTED: &str = "  \x1b[31mbackend\x1b[0m";
+
+    #[test]
+    fn painted_text_pads_by_visible_width() {
+        assert_eq!(display_width(PAINTED), 9);
+        assert_eq!(strip_ansi(PAINTED), "  backend");
+        assert_eq!(pad(PAINTED, 12), format!("{PAINTED}   "));
+        assert_eq!(display_width("中文"), 4);
+        assert_eq!(pad("中文", 6), "中文  ");
+    }
+
+    #[test]
+    fn truncate_keeps_color_codes_balanced() {
+        assert_eq!(truncate(PAINTED, 9), PAINTED);
+        assert_eq!(truncate(PAINTED, 6), "  \x1b[31mbac…\x1b[0m");
+        assert_eq!(truncate("backend", 4), "bac…");
+    }
+
+    #[test]
+    fn wrap_breaks_cjk_by_display_width() {
+        assert_eq!(wrap("测试效果很好", 4), vec!["测试", "效果", "很好"]);
+        // 奇数列宽时放不下的全角字符换到下一行
+        assert_eq!(wrap("测试效果", 5), vec!["测试", "效果"]);
+        assert_eq!(wrap("测试", 1), vec!["测", "试"]);
+        assert_eq!(wrap("", 4), vec![""]);
+    }
+
+    #[test]
+    fn wrap_mixed_width_text() {
+        assert_eq!(
+            wrap("fix 登录页面的样式", 8),
+            vec!["fix 登录", "页面的样", "式"]
+        );
+        assert_eq!(wrap("修复 login bug", 6), vec!["修复", "login", "bug"]);
+        // 空白之后的部分过长时按字符断开
+        assert_eq!(wrap("a 测试效果很好", 6), vec!["a 测试", "效果很", "好"]);
+        for line in wrap("混合 mixed 宽度 width 的文字", 7) {
+            assert!(display_width(&line) <= 7, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn wrap_resets_color_on_every_line() {
+        let lines = wrap("\x1b[31m测试效果\x1b[0m", 4);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.ends_with("\x1b[0m")));
+        assert_eq!(strip_ansi(&lines.join("")), "测试效果");
+    }
+}
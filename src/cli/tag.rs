@@ -0,0 +1,40 @@
+use super::app::AppHandle;
+use super::config::parse_color;
+use super::display::display_tags;
+use super::render::OutputArgs;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum TagCommands {
+    /// List tags and their colors
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Set the color a tag is shown with in reports and charts, or clear it when no color is given
+    Color {
+        tag: String,
+        /// black, red, green, yellow, blue, magenta, cyan or white
+        #[arg(value_parser = parse_color)]
+        color: Option<String>,
+    },
+}
+
+pub fn handle_tag_command(app_handle: &AppHandle, command: TagCommands) {
+    let result =
+        match command {
+            TagCommands::List { output } => app_handle
+                .get_tags()
+                .map(|tags| display_tags(&tags, output.format)),
+            TagCommands::Color { tag, color } => app_handle
+                .set_tag_color(&tag, color.as_deref())
+                .map(|_| match color {
+                    Some(color) => println!("Tag '{tag}' is shown in {color}."),
+                    None => println!("Color of tag '{tag}' cleared."),
+                }),
+        };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
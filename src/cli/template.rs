@@ -0,0 +1,126 @@
+use super::app::AppHandle;
+use super::display::{display_task_templates, format_duration_short};
+use super::render::OutputArgs;
+use super::utils::parse_start_args;
+use crate::core::task_template::TaskTemplate;
+use anyhow::anyhow;
+use clap::Subcommand;
+use dialoguer::{FuzzySelect, theme::ColorfulTheme};
+use std::io::IsTerminal;
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommands {
+    /// Save a template started with "s @name", e.g. add standup "15m #meeting daily standup"
+    Add {
+        name: String,
+        /// Optional duration (e.g. "15m", "pomodoro") followed by the description
+        #[arg(required = true, trailing_var_arg = true)]
+        spec: Vec<String>,
+    },
+    /// List templates
+    #[clap(alias = "ls")]
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Remove a template by its name
+    #[clap(alias = "rm")]
+    Remove { name: String },
+}
+
+pub fn handle_template_command(app_handle: &AppHandle, command: TemplateCommands) {
+    let result = match command {
+        TemplateCommands::Add { name, spec } => {
+            // 整个模板可以写在一个参数中，例如 "15m #meeting daily standup"
+            let words = spec
+                .join(" ")
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            parse_start_args(words)
+                .map_err(|e| anyhow!(e))
+                .and_then(|(duration, desc)| {
+                    app_handle.set_task_template(&name, duration, desc.as_deref())
+                })
+                .map(|_| println!("Template '{}' saved.", name.trim_start_matches('@')))
+        }
+        TemplateCommands::List { output } => app_handle
+            .get_task_templates()
+            .map(|templates| display_task_templates(&templates, output.format)),
+        TemplateCommands::Remove { name } => app_handle
+            .remove_task_template(&name)
+            .map(|_| println!("Template '{}' removed.", name.trim_start_matches('@'))),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+// s @standup [duration] [description]：按模板开始计时，参数中的时长覆盖模板的时长，描述追加在模板之后
+// 只写 @ 时通过模糊搜索选择模板，没有同名模板时返回 None，按普通参数处理（@ 表示项目）
+pub fn template_start_args(
+    app_handle: &AppHandle,
+    args: &[String],
+) -> anyhow::Result<Option<(Option<u64>, Option<String>)>> {
+    let Some(name) = args.first().and_then(|arg| arg.strip_prefix('@')) else {
+        return Ok(None);
+    };
+    let templates = app_handle.get_task_templates()?;
+    let template = if name.is_empty() {
+        pick_template(templates)?
+    } else {
+        match templates.into_iter().find(|template| template.name == name) {
+            // 模板保存之后才创建的同名项目无法区分，提示用户改名
+            Some(_) if app_handle.has_project(name)? => {
+                return Err(anyhow!(
+                    "'@{name}' is both a template and a project. Save the template under another name, or write @{name} after the description to use the project"
+                ));
+            }
+            Some(template) => template,
+            None => return Ok(None),
+        }
+    };
+    let (duration, extra) = parse_start_args(args[1..].to_vec()).map_err(|e| anyhow!(e))?;
+    let desc = [template.desc, extra]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(" ");
+    Ok(Some((
+        duration.or(template.duration),
+        (!desc.is_empty()).then_some(desc),
+    )))
+}
+
+fn pick_template(templates: Vec<TaskTemplate>) -> anyhow::Result<TaskTemplate> {
+    if templates.is_empty() {
+        return Err(anyhow!(
+            "No templates saved, add one with: template add <name> [duration] [description]"
+        ));
+    }
+    // 输入不是终端时无法交互选择
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow!("No template name given, start one with: s @<name>"));
+    }
+    let items: Vec<String> = templates
+        .iter()
+        .map(|template| {
+            let duration = template
+                .duration
+                .map(format_duration_short)
+                .unwrap_or_default();
+            let desc = template.desc.as_deref().unwrap_or_default();
+            format!("{}  {} {}", template.name, duration, desc)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Template")
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+    selection
+        .and_then(|index| templates.into_iter().nth(index))
+        .ok_or_else(|| anyhow!("No template selected"))
+}
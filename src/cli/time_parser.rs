@@ -0,0 +1,236 @@
+use super::config::calendar;
+use super::utils::parse_duration;
+use crate::core::calendar::{Calendar, datetime_in};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+// 解析自然语言的时间表达式，返回 [start, end) 范围（calendar 所在时区的时间）
+// 日期与周期表达式返回整段范围，例如 "yesterday"、"last monday"、"this week"、"last month"
+// 时间点表达式返回 start == end，例如 "now"、"2 hours ago"、"14:30"、"2025-02-13 15:46"
+// 只有时刻（例如 "14:30"）时落在 default_day 这一天
+fn parse_range_at(
+    s: &str,
+    calendar: &Calendar,
+    default_day: NaiveDate,
+) -> Result<(DateTime<Tz>, DateTime<Tz>), String> {
+    let now = Utc::now().with_timezone(&calendar.time_zone);
+    let expr = s.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let expr = expr.to_lowercase();
+    if expr.is_empty() {
+        return Err("Time expression cannot be empty".to_string());
+    }
+
+    if expr == "now" {
+        return Ok((now, now));
+    }
+
+    // 相对时间，例如 "2 hours ago"、"1h30m ago"
+    if let Some(duration) = expr.strip_suffix(" ago") {
+        let duration = parse_duration(duration)?;
+        let time = now - Duration::milliseconds(duration as i64);
+        return Ok((time, time));
+    }
+
+    // 日期加时刻，例如 "2025-02-13 15:46"、"yesterday 9:00"，或只有时刻
+    let (day_expr, time_expr) = match expr.rsplit_once(' ') {
+        Some((day, time)) if parse_time_of_day(time).is_some() => (day, Some(time)),
+        _ if parse_time_of_day(&expr).is_some() => ("", Some(expr.as_str())),
+        _ => (expr.as_str(), None),
+    };
+    if let Some(time_expr) = time_expr {
+        let day = if day_expr.is_empty() {
+            default_day
+        } else {
+            parse_day(day_expr, calendar.today())?
+        };
+        let time = parse_time_of_day(time_expr).expect("time of day is checked above");
+        let time = day
+            .and_time(time)
+            .and_local_timezone(calendar.time_zone)
+            .earliest()
+            .ok_or_else(|| format!("Invalid local time: '{s}'"))?;
+        return Ok((time, time));
+    }
+
+    // 日期按每天开始的时刻划分，例如凌晨两点时 "today" 仍是前一天
+    let today = calendar.today();
+    let (first, last) = match expr.as_str() {
+        "this week" => {
+            let start = calendar.start_of_week(today);
+            (start, start + Duration::days(7))
+        }
+        "last week" => {
+            let start = calendar.start_of_week(today) - Duration::days(7);
+            (start, start + Duration::days(7))
+        }
+        "this month" => {
+            let start = month_start(today);
+            (start, next_month_start(start))
+        }
+        "last month" => {
+            let end = month_start(today);
+            (month_start(end - Duration::days(1)), end)
+        }
+        "this year" => {
+            let start = year_start(today.year())?;
+            (start, year_start(today.year() + 1)?)
+        }
+        "last year" => {
+            let end = year_start(today.year())?;
+            (year_start(today.year() - 1)?, end)
+        }
+        _ => {
+            let day = parse_day(&expr, today)?;
+            (day, day + Duration::days(1))
+        }
+    };
+    Ok((
+        datetime_in(calendar.day_start(first), calendar.time_zone),
+        datetime_in(calendar.day_start(last), calendar.time_zone),
+    ))
+}
+
+// 解析某一天：today、yesterday、tomorrow、monday（本周已过去或今天的周一）、last monday、YYYY-MM-DD
+fn parse_day(expr: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    match expr {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        _ => {}
+    }
+    if let Some(weekday) = expr.strip_prefix("last ").and_then(parse_weekday) {
+        return Ok(last_weekday(today - Duration::days(1), weekday));
+    }
+    if let Some(weekday) = parse_weekday(expr) {
+        return Ok(last_weekday(today, weekday));
+    }
+    NaiveDate::parse_from_str(expr, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(expr, "%Y/%m/%d"))
+        .map_err(|_| {
+            format!(
+                "Invalid time: '{expr}'. Expected e.g. \"today\", \"last monday\", \"2 hours ago\", \"14:30\", \"2025-02-13 15:46\" or \"this week\""
+            )
+        })
+}
+
+fn parse_weekday(expr: &str) -> Option<Weekday> {
+    // chrono 支持 "mon" 与 "monday" 两种写法
+    expr.parse::<Weekday>().ok()
+}
+
+// 不晚于 day 的最近一个 weekday
+fn last_weekday(day: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_back = (day.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    day - Duration::days(days_back as i64)
+}
+
+fn parse_time_of_day(expr: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(expr, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(expr, "%H:%M"))
+        .ok()
+}
+
+fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).expect("the first day of a month is valid")
+}
+
+fn next_month_start(start: NaiveDate) -> NaiveDate {
+    month_start(start + Duration::days(31))
+}
+
+fn year_start(year: i32) -> Result<NaiveDate, String> {
+    NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| format!("Invalid year: {year}"))
+}
+
+// 解析时间范围，返回毫秒时间戳 [start, end)
+pub fn parse_time_range(s: &str) -> Result<(u64, u64), String> {
+    let calendar = calendar();
+    parse_range_at(s, &calendar, calendar.today()).map(|(start, end)| {
+        (
+            start.timestamp_millis() as u64,
+            end.timestamp_millis() as u64,
+        )
+    })
+}
+
+// 解析时间点，日期与周期表达式取其开始时间
+pub fn parse_time(s: &str) -> Result<u64, String> {
+    parse_time_on(s, calendar().today())
+}
+
+// 解析时间点，只有时刻时落在 day 这一天，用于修改已有的记录
+pub fn parse_time_on(s: &str, day: NaiveDate) -> Result<u64, String> {
+    parse_range_at(s, &calendar(), day).map(|(start, _)| start.timestamp_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_calendar() -> Calendar {
+        Calendar {
+            time_zone: Tz::UTC,
+            week_start: Weekday::Mon,
+            day_offset: 0,
+            split_days: true,
+        }
+    }
+
+    fn parse(s: &str) -> Result<(DateTime<Tz>, DateTime<Tz>), String> {
+        let calendar = utc_calendar();
+        parse_range_at(s, &calendar, calendar.today())
+    }
+
+    #[test]
+    fn parse_date_and_time() {
+        let (start, end) = parse("2025-02-13 15:46").unwrap();
+        assert_eq!(start, end);
+        assert_eq!(start.to_rfc3339(), "2025-02-13T15:46:00+00:00");
+    }
+
+    #[test]
+    fn parse_time_of_day_on_default_day() {
+        let calendar = utc_calendar();
+        let day = NaiveDate::from_ymd_opt(2025, 2, 13).unwrap();
+        let (start, _) = parse_range_at("9:05", &calendar, day).unwrap();
+        assert_eq!(start.to_rfc3339(), "2025-02-13T09:05:00+00:00");
+    }
+
+    #[test]
+    fn parse_this_week() {
+        let (start, end) = parse("this week").unwrap();
+        assert_eq!(start.weekday(), Weekday::Mon);
+        assert_eq!(start.time(), NaiveTime::MIN);
+        assert_eq!(end - start, Duration::days(7));
+        assert!(start.date_naive() <= utc_calendar().today());
+        assert!(end.date_naive() > utc_calendar().today());
+    }
+
+    #[test]
+    fn parse_last_monday() {
+        let today = utc_calendar().today();
+        let (start, end) = parse("last monday").unwrap();
+        assert_eq!(start.weekday(), Weekday::Mon);
+        assert_eq!(end - start, Duration::days(1));
+        assert!(start.date_naive() < today);
+        assert!(today - start.date_naive() <= Duration::days(7));
+    }
+
+    #[test]
+    fn parse_relative_time() {
+        let now = Utc::now();
+        let (start, _) = parse("2 hours ago").unwrap();
+        let elapsed = now - start.with_timezone(&Utc);
+        assert!(elapsed >= Duration::hours(2) - Duration::seconds(5));
+        assert!(elapsed <= Duration::hours(2) + Duration::seconds(5));
+    }
+
+    #[test]
+    fn reject_invalid_expressions() {
+        assert!(parse("").is_err());
+        assert!(parse("next blursday").is_err());
+        assert!(parse("2025-02-30").is_err());
+        assert!(parse("25:00").is_err());
+        assert!(parse("9999999999999999999h ago").is_err());
+    }
+}
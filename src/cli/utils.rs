@@ -1,6 +1,17 @@
+use std::fmt;
 use std::time::SystemTime;
 
-use chrono::{DateTime, Local};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+
+use super::config::{config, time_zone, with_config};
+use super::time_parser::parse_time_range;
+use crate::core::{
+    calendar::datetime_in,
+    goal::{GoalKind, GoalPeriod},
+    tag::normalize_tag_path,
+};
 
 pub fn get_current_time() -> u64 {
     if let Ok(d) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
@@ -10,64 +21,132 @@ pub fn get_current_time() -> u64 {
     }
 }
 
-pub fn get_datetime(time: u64) -> DateTime<Local> {
-    let naive = DateTime::from_timestamp_millis(time as i64).expect("Invalid timestamp");
-    DateTime::from(naive)
+// 在当前使用的时区中展示
+pub fn get_datetime(time: u64) -> DateTime<Tz> {
+    datetime_in(time, time_zone())
 }
 
-// 从毫秒单位的 unix 时间戳获取日期时间字符串
+// 从毫秒单位的 unix 时间戳获取日期时间字符串，格式由配置文件中的 format.date 与 format.time 决定
 pub fn get_datetime_str(time: u64) -> String {
-    let naive = DateTime::from_timestamp_millis(time as i64).expect("Invalid timestamp");
-    let datetime: DateTime<Local> = DateTime::from(naive);
-    datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    let datetime = get_datetime(time);
+    with_config(|config| {
+        datetime
+            .format(&format!("{} {}", config.format.date, config.format.time))
+            .to_string()
+    })
 }
 // 获取日期
 pub fn get_date_str(time: u64) -> String {
-    let naive = DateTime::from_timestamp_millis(time as i64).expect("Invalid timestamp");
-    let datetime: DateTime<Local> = DateTime::from(naive);
-    datetime.format("%Y-%m-%d").to_string()
+    format_date(get_datetime(time).date_naive())
+}
+// 获取时刻
+pub fn get_time_str(time: u64) -> String {
+    let datetime = get_datetime(time);
+    with_config(|config| datetime.format(&config.format.time).to_string())
 }
 
-pub fn parse_duration(s: &str) -> Result<u64, String> {
-    if s.is_empty() {
-        return Err("Duration string cannot be empty".to_string());
-    }
-    if let Some(last_char) = s.chars().last() {
-        // 检查最后一个字符是否为数字，实则判断整个字符串是否为纯数字
-        if last_char.is_digit(10) {
-            // 纯数字视为秒
-            if s.chars().all(|c| c.is_digit(10)) {
-                return s
-                    .parse::<u64>()
-                    .map(|seconds| seconds * 1000)
-                    .map_err(|_| format!("Invalid number: '{}'", s));
-            }
+pub fn format_date(date: NaiveDate) -> String {
+    with_config(|config| date.format(&config.format.date).to_string())
+}
+
+// 检查 chrono 的格式字符串，格式化时遇到无效的格式会导致程序崩溃
+pub fn check_time_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!(
+            "Invalid format: '{format}'. Expected a chrono format like \"%Y-%m-%d\" or \"%H:%M\""
+        ));
+    }
+    Ok(())
+}
+
+// 时长的上限（十年），超出的时长视为输入错误，避免计算结束时间时溢出
+const MAX_DURATION: f64 = 10.0 * 366.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+// 解析时长失败的原因，过长的时长是写错了的时长，而不是其它文字
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurationError {
+    Invalid(String),
+    TooLong(String),
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DurationError::Invalid(message) => f.write_str(message),
+            DurationError::TooLong(s) => write!(f, "Duration is too long: '{s}'"),
         }
-        if last_char.is_alphabetic() {
-            // 判断是否为带单位的时间
-            // 解析带单位的时间
-            let (num_str, unit) = s.split_at(s.len() - last_char.len_utf8());
-            let num = num_str
-                .parse::<u64>()
-                .map_err(|_| format!("Invalid number: '{}'", num_str))?;
-            return match unit {
-                "s" => Ok(num * 1000),      // 秒转毫秒
-                "m" => Ok(num * 60 * 1000), // 分钟转毫秒
-                _ => Err(format!(
-                    "Invalid time unit: '{}'. Expected 's' or 'm'",
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+impl From<DurationError> for String {
+    fn from(e: DurationError) -> Self {
+        e.to_string()
+    }
+}
+
+// 解析时长，纯数字视为秒，也支持带单位与组合的写法，例如 "15m"、"1h30m"、"90min"、"1.5h"、"2 hours"
+pub fn parse_duration(s: &str) -> Result<u64, DurationError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(DurationError::Invalid(
+            "Duration string cannot be empty".to_string(),
+        ));
+    }
+    let too_long = || DurationError::TooLong(s.to_string());
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        let seconds = s.parse::<u64>().map_err(|_| too_long())?;
+        return seconds
+            .checked_mul(1000)
+            .filter(|ms| *ms as f64 <= MAX_DURATION)
+            .ok_or_else(too_long);
+    }
+
+    let mut total = 0.0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        // 依次读取 "数字 单位" 组合
+        let num_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (num_str, tail) = rest.split_at(num_len);
+        let num = num_str
+            .parse::<f64>()
+            .map_err(|_| DurationError::Invalid(format!("Invalid duration string '{s}'")))?;
+        let tail = tail.trim_start();
+        let unit_len = tail
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let unit_ms = match unit.to_lowercase().as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1000.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0 * 1000.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60.0 * 60.0 * 1000.0,
+            "d" | "day" | "days" => 24.0 * 60.0 * 60.0 * 1000.0,
+            "w" | "week" | "weeks" => 7.0 * 24.0 * 60.0 * 60.0 * 1000.0,
+            _ => {
+                return Err(DurationError::Invalid(format!(
+                    "Invalid time unit: '{}'. Expected e.g. 's', 'm', 'h' or 'd'",
                     unit
-                )),
-            };
-        }
+                )));
+            }
+        };
+        total += num * unit_ms;
+        rest = tail.trim_start();
+    }
+    if !total.is_finite() || total > MAX_DURATION {
+        return Err(too_long());
     }
-    return Err(format!("Invalid duration string '{s}'"));
+    Ok(total.round() as u64)
 }
 
 // s 15m "#code 编写 timeLog"
 // <command> [duration] [desc]
-pub fn parse_start_args(args: Vec<String>) -> (Option<u64>, Option<String>) {
+// 第一个参数是超出范围的时长时返回错误，不当作描述
+pub fn parse_start_args(args: Vec<String>) -> Result<(Option<u64>, Option<String>), String> {
     if args.is_empty() {
-        return (None, None);
+        return Ok((None, None));
     }
     // 尝试解析第一个参数作为持续时间
     let mut duration = None;
@@ -75,7 +154,11 @@ pub fn parse_start_args(args: Vec<String>) -> (Option<u64>, Option<String>) {
 
     // 先尝试将第一个参数解析为持续时间
     if let Some(first) = args.first() {
-        if let Ok(d) = parse_duration(first) {
+        let parsed = parse_duration(first);
+        if let Err(e @ DurationError::TooLong(_)) = parsed {
+            return Err(e.into());
+        }
+        if let Ok(d) = parsed.or_else(|_| parse_pomodoro(first)) {
             duration = Some(d);
             // 如果还有其他参数，将剩余所有参数合并为描述
             if args.len() > 1 {
@@ -86,13 +169,131 @@ pub fn parse_start_args(args: Vec<String>) -> (Option<u64>, Option<String>) {
             desc = Some(args.join(" "));
         }
     }
-    (duration, desc)
+    Ok((duration, desc))
 }
 
+// 番茄钟关键字，时长在配置文件的 pomodoro 中设置
+fn parse_pomodoro(s: &str) -> Result<u64, DurationError> {
+    let pomodoro = config().pomodoro;
+    match s {
+        "pomodoro" | "pomo" => parse_duration(&pomodoro.work),
+        "break" => parse_duration(&pomodoro.short_break),
+        "long-break" => parse_duration(&pomodoro.long_break),
+        _ => Err(DurationError::Invalid(format!(
+            "Invalid duration string '{s}'"
+        ))),
+    }
+}
+
+// 标签支持层级写法，例如 #client/acme/backend
 pub fn parse_tags(input: &str) -> Vec<String> {
-    input
+    let mut tags: Vec<String> = vec![];
+    for tag in input
         .split_whitespace()
         .filter(|word| word.starts_with('#'))
-        .map(|tag| tag.trim_start_matches('#').to_string())
-        .collect()
+        .map(|tag| normalize_tag_path(tag.trim_start_matches('#')))
+    {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+// 解析命令行中的标签筛选参数，"#client/acme" 与 "client/acme" 等价
+pub fn parse_tag_filter(tags: &[String]) -> Option<Vec<String>> {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| normalize_tag_path(tag.trim_start_matches('#')))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if tags.is_empty() { None } else { Some(tags) }
+}
+
+// 解析描述中的项目，例如 "@acme-site #code 修复登录"，只取第一个 @project
+pub fn parse_project(input: &str) -> Option<String> {
+    input
+        .split_whitespace()
+        .find(|word| word.starts_with('@') && word.len() > 1)
+        .map(|word| word.trim_start_matches('@').to_string())
+}
+
+// 解析日期或时间表达式，返回其开始时间的毫秒时间戳，例如 "2025-02-01"、"last monday"、"this week"
+pub fn parse_date(s: &str) -> Result<u64, String> {
+    parse_time_range(s).map(|(start, _)| start)
+}
+
+// 解析日期或时间表达式，返回其结束时间的毫秒时间戳，日期表达式包含当天
+pub fn parse_date_end(s: &str) -> Result<u64, String> {
+    parse_time_range(s).map(|(_, end)| end)
+}
+
+// 解析目标描述：[min|max] <duration> #tag per <day|week>
+// 例如 "20h #deepwork per week"、"max 2h #meeting per day"、"1h #reading daily"
+pub fn parse_goal(args: &[String]) -> Result<(GoalKind, u64, String, GoalPeriod), String> {
+    let words: Vec<&str> = args.iter().flat_map(|arg| arg.split_whitespace()).collect();
+    let mut kind = GoalKind::Min;
+    let mut target = None;
+    let mut tag = None;
+    let mut period = None;
+    for word in words {
+        if let Ok(k) = word.parse::<GoalKind>() {
+            kind = k;
+        } else if let Some(name) = word.strip_prefix('#') {
+            tag = Some(normalize_tag_path(name));
+        } else if let Ok(p) = word.parse::<GoalPeriod>() {
+            period = Some(p);
+        } else if word == "per" {
+            continue;
+        } else {
+            target = Some(parse_duration(word)?);
+        }
+    }
+    let target = target.ok_or("Goal duration is missing (e.g. \"20h\")")?;
+    let tag = tag
+        .filter(|tag| !tag.is_empty())
+        .ok_or("Goal tag is missing (e.g. \"#deepwork\")")?;
+    let period = period.ok_or("Goal period is missing (e.g. \"per week\")")?;
+    Ok((kind, target, tag, period))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60 * 1000;
+    const HOUR: u64 = 60 * MINUTE;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90"), Ok(90 * 1000));
+        assert_eq!(parse_duration("15m"), Ok(15 * MINUTE));
+        assert_eq!(parse_duration("1h30m"), Ok(90 * MINUTE));
+        assert_eq!(parse_duration("1.5h"), Ok(90 * MINUTE));
+        assert_eq!(parse_duration("90min"), Ok(90 * MINUTE));
+        assert_eq!(parse_duration("2 hours"), Ok(2 * HOUR));
+        assert_eq!(parse_duration(" 1d 2h "), Ok(26 * HOUR));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_input() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("abc").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("1..5h").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflowing_input() {
+        assert!(parse_duration("9999999999999999999h").is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+        assert!(parse_duration("18446744073709551615").is_err());
+        assert!(parse_duration(&format!("{}h", "9".repeat(400))).is_err());
+        assert_eq!(
+            parse_duration("100000w"),
+            Err(DurationError::TooLong("100000w".to_string()))
+        );
+        assert_eq!(parse_duration("52w"), Ok(52 * 7 * 24 * HOUR));
+    }
 }
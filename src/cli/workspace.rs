@@ -0,0 +1,195 @@
+use super::app::AppHandle;
+use crate::core::database::Database;
+use anyhow::anyhow;
+use clap::Subcommand;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+// 环境变量指定数据库文件时优先于工作区
+pub const DB_ENV_VAR: &str = "AKASHIC_LOG_DB";
+
+#[derive(Subcommand, Debug)]
+pub enum WorkspaceCommands {
+    /// Switch to a workspace, it is created on first use
+    Use { name: String },
+    /// List workspaces
+    #[clap(alias = "ls")]
+    List,
+    /// Show the current workspace and its database file
+    Current,
+}
+
+// 数据目录，遵循 XDG 规范，例如 ~/.local/share/akashic_log
+pub fn data_dir() -> anyhow::Result<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| dir.join("akashic_log"))
+        .ok_or_else(|| anyhow!("Can't find the data directory, use --db to set the database file"))
+}
+
+// 默认工作区的数据库为 akashic_log.db，其它工作区保存在 workspaces 目录下
+pub fn workspace_db_path(name: &str) -> anyhow::Result<PathBuf> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Invalid workspace name: '{name}'. Use letters, digits, '-' and '_'"
+        ));
+    }
+    let dir = data_dir()?;
+    if name == DEFAULT_WORKSPACE {
+        Ok(dir.join("akashic_log.db"))
+    } else {
+        Ok(dir.join("workspaces").join(format!("{name}.db")))
+    }
+}
+
+// 上次使用的工作区记录在数据目录下的 workspace 文件中
+pub fn current_workspace() -> String {
+    data_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("workspace")).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+fn save_current_workspace(name: &str) -> anyhow::Result<()> {
+    let dir = data_dir()?;
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("workspace"), name)?;
+    Ok(())
+}
+
+pub fn list_workspaces() -> anyhow::Result<Vec<String>> {
+    let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+    if let Ok(entries) = fs::read_dir(data_dir()?.join("workspaces")) {
+        let mut others: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "db"))
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+            .collect();
+        others.sort();
+        names.extend(others);
+    }
+    Ok(names)
+}
+
+// 打开数据库文件，所在目录不存在时自动创建
+pub fn open_database(path: &Path) -> anyhow::Result<Database> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid database path: {}", path.display()))?;
+    Database::new(path_str)
+}
+
+// 只读打开数据库文件，不创建目录，也不创建表或迁移
+pub fn read_only_database(path: &Path) -> anyhow::Result<Database> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid database path: {}", path.display()))?;
+    Database::open_read_only(path_str)
+}
+
+// 当前使用的工作区与数据库文件，通过 --db 或环境变量指定文件时没有工作区
+pub struct Workspace {
+    pub name: Option<String>,
+    pub db_path: PathBuf,
+}
+
+impl Workspace {
+    // 优先级：--db 参数 > --workspace 参数 > AKASHIC_LOG_DB 环境变量 > 配置文件中的 db_path > 上次使用的工作区
+    pub fn resolve(
+        db: Option<PathBuf>,
+        workspace: Option<String>,
+        config_db: Option<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        // 指定了工作区时不使用环境变量与配置文件中的数据库
+        let db = db.or_else(|| {
+            workspace.is_none().then(|| {
+                std::env::var_os(DB_ENV_VAR)
+                    .map(PathBuf::from)
+                    .or(config_db)
+            })?
+        });
+        if let Some(db_path) = db {
+            return Ok(Workspace {
+                name: None,
+                db_path,
+            });
+        }
+        let name = workspace.unwrap_or_else(current_workspace);
+        let db_path = workspace_db_path(&name)?;
+        Ok(Workspace {
+            name: Some(name),
+            db_path,
+        })
+    }
+}
+
+pub fn handle_workspace_command(
+    app_handle: &mut AppHandle,
+    workspace: &mut Workspace,
+    command: WorkspaceCommands,
+) {
+    let result = match command {
+        WorkspaceCommands::Use { name } => switch_workspace(app_handle, workspace, &name),
+        WorkspaceCommands::List => list_workspaces().map(|names| {
+            for name in names {
+                let mark = if workspace.name.as_deref() == Some(name.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{mark} {name}");
+            }
+        }),
+        WorkspaceCommands::Current => {
+            println!(
+                "Workspace: {} ({})",
+                workspace.name.as_deref().unwrap_or("-"),
+                workspace.db_path.display()
+            );
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+fn switch_workspace(
+    app_handle: &mut AppHandle,
+    workspace: &mut Workspace,
+    name: &str,
+) -> anyhow::Result<()> {
+    // 计时器的记录会写入当前工作区，切换前需要先停止
+    if app_handle
+        .get_current_timer_status()
+        .is_ok_and(|status| status.end_time.is_none())
+    {
+        return Err(anyhow!(
+            "Stop the running timer before switching workspaces"
+        ));
+    }
+    let db_path = workspace_db_path(name)?;
+    *app_handle = AppHandle::new(open_database(&db_path)?);
+    save_current_workspace(name)?;
+    *workspace = Workspace {
+        name: Some(name.to_string()),
+        db_path,
+    };
+    println!(
+        "Switched to workspace '{name}' ({}).",
+        workspace.db_path.display()
+    );
+    Ok(())
+}
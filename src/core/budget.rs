@@ -0,0 +1,351 @@
+use super::calendar::{Calendar, CalendarPeriod};
+use super::project::Project;
+use super::tag::{Tag, is_in_tag_subtree};
+use super::timeline::Timeline;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+// 预算可以设置在项目或标签上
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetTarget {
+    Project,
+    Tag,
+}
+
+impl BudgetTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetTarget::Project => "project",
+            BudgetTarget::Tag => "tag",
+        }
+    }
+}
+
+impl FromStr for BudgetTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "project" => Ok(BudgetTarget::Project),
+            "tag" => Ok(BudgetTarget::Tag),
+            _ => Err(format!(
+                "Invalid budget target: '{s}'. Expected 'project' or 'tag'"
+            )),
+        }
+    }
+}
+
+// 预算周期，total 表示整个项目周期的总预算
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    Total,
+    Day,
+    Week,
+    Month,
+}
+
+impl BudgetPeriod {
+    // total 预算不按日历周期统计
+    pub fn calendar_period(&self) -> Option<CalendarPeriod> {
+        match self {
+            BudgetPeriod::Total => None,
+            BudgetPeriod::Day => Some(CalendarPeriod::Day),
+            BudgetPeriod::Week => Some(CalendarPeriod::Week),
+            BudgetPeriod::Month => Some(CalendarPeriod::Month),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Total => "total",
+            BudgetPeriod::Day => "day",
+            BudgetPeriod::Week => "week",
+            BudgetPeriod::Month => "month",
+        }
+    }
+}
+
+impl FromStr for BudgetPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "total" => Ok(BudgetPeriod::Total),
+            "day" | "daily" => Ok(BudgetPeriod::Day),
+            "week" | "weekly" => Ok(BudgetPeriod::Week),
+            "month" | "monthly" => Ok(BudgetPeriod::Month),
+            _ => Err(format!(
+                "Invalid budget period: '{s}'. Expected 'total', 'day', 'week' or 'month'"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: u64,
+    pub target: BudgetTarget,
+    pub target_id: u64,
+    // 项目名称或标签路径，查询时一并取出方便展示
+    pub target_name: String,
+    // 预算时长（毫秒）
+    pub amount: u64,
+    pub period: BudgetPeriod,
+}
+
+// 预算在当前周期的使用情况
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub used: u64,
+    pub amount: u64,
+}
+
+// 燃尽图中的一天：当天使用的时长与当天结束时剩余的预算
+#[derive(Debug, Clone)]
+pub struct BurnDownDay {
+    pub date: NaiveDate,
+    pub used: u64,
+    pub remaining: i64,
+}
+
+impl BudgetStatus {
+    // 剩余时长，超出预算时为负数
+    pub fn remaining(&self) -> i64 {
+        self.amount as i64 - self.used as i64
+    }
+}
+
+impl Budget {
+    // 展示用的名称，例如 "@website" 或 "#client/acme"
+    pub fn label(&self) -> String {
+        match self.target {
+            BudgetTarget::Project => format!("@{}", self.target_name),
+            BudgetTarget::Tag => format!("#{}", self.target_name),
+        }
+    }
+
+    // 判断时间切片是否计入该预算
+    pub fn matches(&self, project: Option<&Project>, tags: &[Tag]) -> bool {
+        match self.target {
+            BudgetTarget::Project => project.is_some_and(|project| project.id == self.target_id),
+            BudgetTarget::Tag => tags
+                .iter()
+                .any(|tag| is_in_tag_subtree(&tag.name, &self.target_name)),
+        }
+    }
+
+    // 按名称判断，用于尚未写入数据库的计时器
+    pub fn matches_names(&self, project: Option<&str>, tags: &[String]) -> bool {
+        match self.target {
+            BudgetTarget::Project => project == Some(self.target_name.as_str()),
+            BudgetTarget::Tag => tags
+                .iter()
+                .any(|tag| is_in_tag_subtree(tag, &self.target_name)),
+        }
+    }
+
+    // 包含 date 的预算周期的第一天，total 预算没有开始时间
+    pub fn period_start(&self, date: NaiveDate, calendar: &Calendar) -> Option<NaiveDate> {
+        self.period
+            .calendar_period()
+            .map(|period| calendar.period_start(date, period))
+    }
+
+    // 每天计入该预算的时长，key 为日期
+    fn daily_usage(
+        &self,
+        timeline: &Timeline,
+        from: Option<NaiveDate>,
+        calendar: &Calendar,
+    ) -> BTreeMap<NaiveDate, u64> {
+        let mut usage = BTreeMap::new();
+        for slice in &timeline.list {
+            let tags = timeline
+                .tags
+                .get(&slice.id)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            if !self.matches(timeline.projects.get(&slice.id), tags) {
+                continue;
+            }
+            let end = slice.end_time.unwrap_or(slice.start_time);
+            for (date, len) in calendar.split_by_day(slice.start_time, end) {
+                if from.is_none_or(|from| date >= from) {
+                    *usage.entry(date).or_insert(0) += len;
+                }
+            }
+        }
+        usage
+    }
+
+    // 当前周期内已使用的预算
+    pub fn status(
+        &self,
+        timeline: &Timeline,
+        today: NaiveDate,
+        calendar: &Calendar,
+    ) -> BudgetStatus {
+        let used = self
+            .daily_usage(timeline, self.period_start(today, calendar), calendar)
+            .values()
+            .sum();
+        BudgetStatus {
+            used,
+            amount: self.amount,
+        }
+    }
+
+    // 当前周期内每个有记录的日期的燃尽数据
+    pub fn burn_down(
+        &self,
+        timeline: &Timeline,
+        today: NaiveDate,
+        calendar: &Calendar,
+    ) -> Vec<BurnDownDay> {
+        let mut remaining = self.amount as i64;
+        self.daily_usage(timeline, self.period_start(today, calendar), calendar)
+            .into_iter()
+            .map(|(date, used)| {
+                remaining -= used as i64;
+                BurnDownDay {
+                    date,
+                    used,
+                    remaining,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time_slice::TimeSlice;
+    use chrono::Weekday;
+    use chrono_tz::Tz;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn calendar() -> Calendar {
+        Calendar {
+            time_zone: Tz::UTC,
+            week_start: Weekday::Mon,
+            day_offset: 0,
+            split_days: true,
+        }
+    }
+
+    // 2025-02-day，2025-02-10 为星期一
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 2, day).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> u64 {
+        date(day)
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp_millis() as u64
+    }
+
+    // 每项为 (日期, 开始的小时, 时长（小时）, 标签, 项目 ID)
+    fn timeline(slices: &[(u32, u32, u64, &str, Option<u64>)]) -> Timeline {
+        let mut timeline = Timeline::new();
+        for (id, &(day, hour, hours, tag, project_id)) in slices.iter().enumerate() {
+            let id = id as u64 + 1;
+            let start = at(day, hour);
+            timeline.push(TimeSlice::new(id, start, Some(start + hours * HOUR)));
+            timeline.tags.insert(id, vec![Tag::new(tag, None)]);
+            if let Some(project_id) = project_id {
+                timeline.projects.insert(
+                    id,
+                    Project {
+                        id: project_id,
+                        name: format!("project{project_id}"),
+                        client_id: None,
+                        client: None,
+                        archived: false,
+                    },
+                );
+            }
+        }
+        timeline
+    }
+
+    fn budget(target: BudgetTarget, name: &str, hours: u64, period: BudgetPeriod) -> Budget {
+        Budget {
+            id: 1,
+            target,
+            target_id: 7,
+            target_name: name.to_string(),
+            amount: hours * HOUR,
+            period,
+        }
+    }
+
+    #[test]
+    fn status_counts_the_current_period() {
+        let timeline = timeline(&[
+            (7, 9, 4, "client/acme", None),
+            (10, 9, 2, "client/acme/backend", None),
+            (11, 9, 3, "client", None),
+            (12, 9, 1, "personal", None),
+        ]);
+        let today = date(12);
+        let total = budget(BudgetTarget::Tag, "client/acme", 10, BudgetPeriod::Total);
+        let status = total.status(&timeline, today, &calendar());
+        assert_eq!(status.used, 6 * HOUR);
+        assert_eq!(status.remaining(), 4 * HOUR as i64);
+        // 每周预算只计入本周（从 10 日星期一开始）的记录
+        let week = budget(BudgetTarget::Tag, "client/acme", 10, BudgetPeriod::Week);
+        assert_eq!(week.status(&timeline, today, &calendar()).used, 2 * HOUR);
+        let day = budget(BudgetTarget::Tag, "client", 2, BudgetPeriod::Day);
+        assert_eq!(
+            day.status(&timeline, date(11), &calendar()).remaining(),
+            -(HOUR as i64)
+        );
+    }
+
+    #[test]
+    fn project_budget_matches_by_project_id() {
+        let timeline = timeline(&[(10, 9, 2, "code", Some(7)), (10, 12, 3, "code", Some(8))]);
+        let budget = budget(BudgetTarget::Project, "project7", 40, BudgetPeriod::Total);
+        assert_eq!(
+            budget.status(&timeline, date(10), &calendar()).used,
+            2 * HOUR
+        );
+    }
+
+    #[test]
+    fn burn_down_tracks_the_remaining_budget_per_day() {
+        let timeline = timeline(&[
+            (10, 9, 2, "client", None),
+            (10, 14, 1, "client", None),
+            // 跨越午夜的记录拆分到两天
+            (11, 22, 4, "client", None),
+            (13, 9, 5, "client", None),
+            // 上周的记录不计入每周预算
+            (9, 9, 8, "client", None),
+        ]);
+        let budget = budget(BudgetTarget::Tag, "client", 10, BudgetPeriod::Week);
+        let days: Vec<(NaiveDate, u64, i64)> = budget
+            .burn_down(&timeline, date(13), &calendar())
+            .into_iter()
+            .map(|day| (day.date, day.used, day.remaining))
+            .collect();
+        let hours = |h: i64| h * HOUR as i64;
+        assert_eq!(
+            days,
+            vec![
+                (date(10), 3 * HOUR, hours(7)),
+                (date(11), 2 * HOUR, hours(5)),
+                (date(12), 2 * HOUR, hours(3)),
+                (date(13), 5 * HOUR, hours(-2)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+
+// 解析时区名称，例如 "Asia/Shanghai"、"UTC"，"local" 表示系统时区
+pub fn parse_time_zone(name: &str) -> Result<Tz, String> {
+    if name.eq_ignore_ascii_case("local") {
+        return Ok(system_time_zone());
+    }
+    name.parse::<Tz>().map_err(|_| {
+        format!(
+            "Invalid time zone: '{name}'. Expected an IANA name like \"Asia/Shanghai\" or \"UTC\""
+        )
+    })
+}
+
+// 系统时区，优先读取 TZ 环境变量，无法识别时使用 UTC
+pub fn system_time_zone() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|name| name.trim_start_matches(':').parse().ok())
+        .or_else(|| iana_time_zone::get_timezone().ok()?.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+// 毫秒时间戳在 time_zone 中的日期时间
+pub fn datetime_in(time: u64, time_zone: Tz) -> DateTime<Tz> {
+    DateTime::from_timestamp_millis(time as i64)
+        .expect("Invalid timestamp")
+        .with_timezone(&time_zone)
+}
+
+// 毫秒时间戳所属的日期，每天从零点之后 day_offset 毫秒开始
+pub fn date_at(time: u64, time_zone: Tz, day_offset: u64) -> NaiveDate {
+    (datetime_in(time, time_zone).naive_local() - Duration::milliseconds(day_offset as i64)).date()
+}
+
+// 统计时使用的日历：按哪个时区划分日期，每天与每周从何时开始
+#[derive(Debug, Clone, Copy)]
+pub struct Calendar {
+    pub time_zone: Tz,
+    pub week_start: Weekday,
+    // 每天开始的时刻距零点的毫秒数，例如设为 4 小时时凌晨的记录算作前一天
+    pub day_offset: u64,
+    // 跨越每天开始时刻的切片是否按时长拆分到两天，否则整段算作开始的那一天
+    pub split_days: bool,
+}
+
+impl Calendar {
+    pub fn today(&self) -> NaiveDate {
+        self.date_of(Utc::now().timestamp_millis() as u64)
+    }
+
+    // 毫秒时间戳所属的日期
+    pub fn date_of(&self, time: u64) -> NaiveDate {
+        date_at(time, self.time_zone, self.day_offset)
+    }
+
+    // 某一天开始时的毫秒时间戳，该时刻因夏令时不存在时取之后最早的有效时刻
+    pub fn day_start(&self, date: NaiveDate) -> u64 {
+        let start = date.and_hms_opt(0, 0, 0).expect("midnight is valid")
+            + Duration::milliseconds(self.day_offset as i64);
+        (0..24)
+            .find_map(|hour| {
+                (start + Duration::hours(hour))
+                    .and_local_timezone(self.time_zone)
+                    .earliest()
+            })
+            .map(|datetime| datetime.timestamp_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    // 将 [start, end) 按日期划分，返回每天的时长
+    pub fn split_by_day(&self, start: u64, end: u64) -> Vec<(NaiveDate, u64)> {
+        if end <= start {
+            return vec![];
+        }
+        let mut date = self.date_of(start);
+        if !self.split_days {
+            return vec![(date, end - start)];
+        }
+        let mut parts = vec![];
+        let mut from = start;
+        while from < end {
+            let to = end.min(self.day_start(date + Duration::days(1)));
+            if to > from {
+                parts.push((date, to - from));
+                from = to;
+            }
+            date += Duration::days(1);
+        }
+        parts
+    }
+
+    // 从一周的第一天开始的七个星期
+    pub fn weekdays(&self) -> Vec<Weekday> {
+        std::iter::successors(Some(self.week_start), |day| Some(day.succ()))
+            .take(7)
+            .collect()
+    }
+
+    // 包含 date 的那一周的第一天
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(date.weekday().days_since(self.week_start) as i64)
+    }
+
+    // 包含 date 的周期的第一天
+    pub fn period_start(&self, date: NaiveDate, period: CalendarPeriod) -> NaiveDate {
+        match period {
+            CalendarPeriod::Day => date,
+            CalendarPeriod::Week => self.start_of_week(date),
+            CalendarPeriod::Month => date.with_day(1).expect("first day of month is valid"),
+        }
+    }
+}
+
+// 预算与目标统计所用的日历周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn calendar(time_zone: Tz, day_offset_hours: u64, split_days: bool) -> Calendar {
+        Calendar {
+            time_zone,
+            week_start: Weekday::Mon,
+            day_offset: day_offset_hours * HOUR,
+            split_days,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // time_zone 中某天 hour:minute 的毫秒时间戳
+    fn local(time_zone: Tz, day: NaiveDate, hour: u32, minute: u32) -> u64 {
+        time_zone
+            .from_local_datetime(&day.and_hms_opt(hour, minute, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .timestamp_millis() as u64
+    }
+
+    #[test]
+    fn date_of_respects_the_day_offset() {
+        let tz = chrono_tz::Asia::Shanghai;
+        let day = date(2025, 2, 13);
+        let midnight = calendar(tz, 0, true);
+        let four = calendar(tz, 4, true);
+        assert_eq!(midnight.date_of(local(tz, day, 0, 30)), day);
+        assert_eq!(four.date_of(local(tz, day, 0, 30)), date(2025, 2, 12));
+        assert_eq!(four.date_of(local(tz, day, 3, 59)), date(2025, 2, 12));
+        assert_eq!(four.date_of(local(tz, day, 4, 0)), day);
+        // 同一时刻在不同时区属于不同的日期
+        let utc = calendar(Tz::UTC, 0, true);
+        assert_eq!(utc.date_of(local(tz, day, 7, 0)), date(2025, 2, 12));
+    }
+
+    #[test]
+    fn day_start_is_midnight_plus_offset() {
+        let tz = chrono_tz::Asia::Shanghai;
+        let day = date(2025, 2, 13);
+        assert_eq!(calendar(tz, 0, true).day_start(day), local(tz, day, 0, 0));
+        assert_eq!(calendar(tz, 4, true).day_start(day), local(tz, day, 4, 0));
+        let four = calendar(tz, 4, true);
+        assert_eq!(four.date_of(four.day_start(day)), day);
+        assert_eq!(four.date_of(four.day_start(day) - 1), date(2025, 2, 12));
+    }
+
+    #[test]
+    fn split_by_day_cuts_at_the_day_start() {
+        let tz = Tz::UTC;
+        let day = date(2025, 2, 13);
+        let next = date(2025, 2, 14);
+        let start = local(tz, day, 22, 0);
+        let end = local(tz, next, 5, 0);
+        assert_eq!(
+            calendar(tz, 0, true).split_by_day(start, end),
+            vec![(day, 2 * HOUR), (next, 5 * HOUR)]
+        );
+        assert_eq!(
+            calendar(tz, 4, true).split_by_day(start, end),
+            vec![(day, 6 * HOUR), (next, HOUR)]
+        );
+        // 不拆分时整段算作开始的那一天
+        assert_eq!(
+            calendar(tz, 0, false).split_by_day(start, end),
+            vec![(day, 7 * HOUR)]
+        );
+        // 正好在午夜结束的记录不会在第二天留下空的部分
+        assert_eq!(
+            calendar(tz, 0, true).split_by_day(start, local(tz, next, 0, 0)),
+            vec![(day, 2 * HOUR)]
+        );
+        assert!(calendar(tz, 0, true).split_by_day(end, start).is_empty());
+    }
+
+    #[test]
+    fn split_by_day_across_several_days() {
+        let tz = Tz::UTC;
+        let parts = calendar(tz, 0, true).split_by_day(
+            local(tz, date(2025, 2, 13), 12, 0),
+            local(tz, date(2025, 2, 15), 6, 0),
+        );
+        assert_eq!(
+            parts,
+            vec![
+                (date(2025, 2, 13), 12 * HOUR),
+                (date(2025, 2, 14), 24 * HOUR),
+                (date(2025, 2, 15), 6 * HOUR),
+            ]
+        );
+    }
+
+    #[test]
+    fn daylight_saving_days_have_their_real_length() {
+        let tz = chrono_tz::America::New_York;
+        let calendar = calendar(tz, 0, true);
+        // 2025-03-09 夏令时开始，当天只有 23 小时；2025-11-02 夏令时结束，当天有 25 小时
+        for (day, hours) in [(date(2025, 3, 9), 23), (date(2025, 11, 2), 25)] {
+            let start = calendar.day_start(day);
+            let end = calendar.day_start(day + Duration::days(1));
+            assert_eq!(end - start, hours * HOUR);
+            let parts = calendar.split_by_day(start - HOUR, end + HOUR);
+            assert_eq!(parts[1], (day, hours * HOUR));
+        }
+    }
+
+    #[test]
+    fn day_start_skips_a_missing_local_time() {
+        let tz = chrono_tz::America::New_York;
+        // 2025-03-09 的 02:00 - 03:00 不存在，每天从 2 点开始时取 03:00
+        let calendar = calendar(tz, 2, true);
+        let day = date(2025, 3, 9);
+        assert_eq!(calendar.day_start(day), local(tz, day, 3, 0));
+        assert_eq!(calendar.date_of(calendar.day_start(day)), day);
+    }
+}
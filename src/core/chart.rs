@@ -0,0 +1,82 @@
+use super::calendar::Calendar;
+use super::tag::tag_ancestors;
+use super::timeline::Timeline;
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+// 没有标签的记录在图表中的名称
+pub const UNTAGGED: &str = "(untagged)";
+
+// 图表中的一天：当天每个顶层标签的时长（毫秒）
+#[derive(Debug, Clone)]
+pub struct ChartDay {
+    pub date: NaiveDate,
+    pub tags: BTreeMap<String, u64>,
+}
+
+impl ChartDay {
+    pub fn total(&self) -> u64 {
+        self.tags.values().sum()
+    }
+}
+
+// 统计 [from, to] 内每天各顶层标签的时长，没有记录的日期同样列出
+// 有多个标签的记录只计入第一个标签，使每天各标签的时长之和等于当天的总时长
+pub fn daily_breakdown(
+    timeline: &Timeline,
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar: &Calendar,
+) -> Vec<ChartDay> {
+    let mut days: BTreeMap<NaiveDate, BTreeMap<String, u64>> = BTreeMap::new();
+    let mut date = from;
+    while date <= to {
+        days.insert(date, BTreeMap::new());
+        date += Duration::days(1);
+    }
+    for slice in &timeline.list {
+        let tag = timeline
+            .tags
+            .get(&slice.id)
+            .and_then(|tags| tags.first())
+            .and_then(|tag| tag_ancestors(&tag.name).into_iter().next())
+            .unwrap_or_else(|| UNTAGGED.to_string());
+        let end = slice.end_time.unwrap_or(slice.start_time);
+        for (date, len) in calendar.split_by_day(slice.start_time, end) {
+            if let Some(tags) = days.get_mut(&date) {
+                *tags.entry(tag.clone()).or_insert(0) += len;
+            }
+        }
+    }
+    days.into_iter()
+        .map(|(date, tags)| ChartDay { date, tags })
+        .collect()
+}
+
+// 热力图中除没有记录外的深浅等级数
+pub const HEAT_LEVELS: usize = 4;
+
+// 按周排列每天的数据，每周从 calendar 的一周第一天开始，范围之外的日期为 None
+pub fn weeks<'a>(days: &'a [ChartDay], calendar: &Calendar) -> Vec<[Option<&'a ChartDay>; 7]> {
+    let mut weeks: Vec<[Option<&ChartDay>; 7]> = vec![];
+    let mut week_start = None;
+    for day in days {
+        let start = calendar.start_of_week(day.date);
+        if week_start != Some(start) {
+            weeks.push([None; 7]);
+            week_start = Some(start);
+        }
+        if let Some(week) = weeks.last_mut() {
+            week[(day.date - start).num_days() as usize] = Some(day);
+        }
+    }
+    weeks
+}
+
+// 时长对应的热度等级，0 表示没有记录，max 为最忙一天的时长
+pub fn heat_level(total: u64, max: u64) -> usize {
+    if total == 0 || max == 0 {
+        return 0;
+    }
+    ((total as f64 / max as f64 * HEAT_LEVELS as f64).ceil() as usize).clamp(1, HEAT_LEVELS)
+}
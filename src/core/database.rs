@@ -1,8 +1,20 @@
 use std::collections::HashMap;
 
-use super::{description::Description, tag::Tag, time_slice::TimeSlice};
+use super::{
+    budget::{Budget, BudgetPeriod, BudgetTarget},
+    client::Client,
+    description::Description,
+    goal::{Goal, GoalKind, GoalPeriod},
+    invoice::{Invoice, invoice_number},
+    project::Project,
+    rate::{Rate, RateTarget},
+    search::{HIGHLIGHT_END, HIGHLIGHT_START, SearchHit, desegment_cjk, segment_cjk},
+    tag::{Tag, tag_ancestors},
+    task_template::TaskTemplate,
+    time_slice::{OpenTimeSlice, TimeSlice},
+};
 use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
 pub struct Database {
     pub conn: Connection,
@@ -15,25 +27,50 @@ impl Database {
         Ok(Database { conn })
     }
 
+    // 只读打开，不创建表也不迁移，供状态栏等频繁调用的场景使用
+    pub fn open_read_only(path: &str) -> Result<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok(Database { conn })
+    }
+
     fn init_tables(conn: &Connection) -> Result<()> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS time_slices (
                     id INTEGER PRIMARY KEY AUTOINCREMENT,
                     start_time DATETIME NOT NULL,
                     end_time DATETIME,
+                    billable INTEGER NOT NULL DEFAULT 1,
                     CHECK (end_time IS NULL OR end_time > start_time)
             )",
             [],
         )?;
+        Self::ensure_column(
+            conn,
+            "time_slices",
+            "billable",
+            "INTEGER NOT NULL DEFAULT 1",
+        )?;
+        Self::ensure_column(
+            conn,
+            "time_slices",
+            "invoice_id",
+            "INTEGER REFERENCES invoices(id)",
+        )?;
+        Self::ensure_column(conn, "time_slices", "time_zone", "TEXT")?;
+        Self::ensure_column(conn, "time_slices", "planned_end", "INTEGER")?;
 
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tags (
                     id INTEGER PRIMARY KEY AUTOINCREMENT,
                     name TEXT UNIQUE NOT NULL,
-                    color TEXT
+                    color TEXT,
+                    parent_id INTEGER,
+                    FOREIGN KEY (parent_id) REFERENCES tags(id) ON DELETE CASCADE
             )",
             [],
         )?;
+        // 旧版本的数据库没有 parent_id 字段
+        Self::ensure_column(conn, "tags", "parent_id", "INTEGER REFERENCES tags(id)")?;
 
         conn.execute(
             "CREATE TABLE IF NOT EXISTS time_slice_Tags  (
@@ -56,19 +93,195 @@ impl Database {
             [],
         )?;
 
+        // 描述的全文索引，rowid 为时间片段 ID，内容为按中日韩字符切分后的描述
+        // 插入与更新在 Rust 中同步（需要切分文本），删除由触发器同步
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS time_slice_descriptions_fts USING fts5(description)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS time_slice_descriptions_fts_delete
+             AFTER DELETE ON time_slice_descriptions BEGIN
+                 DELETE FROM time_slice_descriptions_fts WHERE rowid = old.time_slice_id;
+             END",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS time_slices_fts_delete
+             AFTER DELETE ON time_slices BEGIN
+                 DELETE FROM time_slice_descriptions_fts WHERE rowid = old.id;
+             END",
+            [],
+        )?;
+        Self::ensure_description_index(conn)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clients (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT UNIQUE NOT NULL,
+                    archived INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS projects (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT UNIQUE NOT NULL,
+                    client_id INTEGER,
+                    archived INTEGER NOT NULL DEFAULT 0,
+                    FOREIGN KEY (client_id) REFERENCES clients(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        // 时间切片与项目的关联，一个时间切片最多属于一个项目
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS time_slice_projects (
+                    time_slice_id INTEGER PRIMARY KEY NOT NULL,
+                    project_id INTEGER NOT NULL,
+                    FOREIGN KEY (time_slice_id) REFERENCES time_slices(id) ON DELETE CASCADE,
+                    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // 计费费率，target_type 为 project、client 或 tag
+        // effective_from 为生效时间，同一对象可以有多条费率记录形成历史
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rates (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    target_type TEXT NOT NULL,
+                    target_id INTEGER NOT NULL,
+                    hourly_rate REAL NOT NULL,
+                    effective_from INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (target_type, target_id, effective_from)
+            )",
+            [],
+        )?;
+
+        // 目标，period 为 day 或 week，kind 为 min 或 max，target 为目标时长（毫秒）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS goals (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    tag TEXT NOT NULL,
+                    period TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    target INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // 时间预算，target_type 为 project 或 tag，period 为 total、day、week 或 month
+        // amount 为预算时长（毫秒），同一对象同一周期只保留一条
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS budgets (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    target_type TEXT NOT NULL,
+                    target_id INTEGER NOT NULL,
+                    period TEXT NOT NULL,
+                    amount INTEGER NOT NULL,
+                    UNIQUE (target_type, target_id, period)
+            )",
+            [],
+        )?;
+
+        // 发票，seq 为顺序编号
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS invoices (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    seq INTEGER UNIQUE NOT NULL,
+                    client TEXT,
+                    project TEXT,
+                    start_time INTEGER,
+                    end_time INTEGER,
+                    created_at INTEGER NOT NULL,
+                    total REAL NOT NULL,
+                    currency TEXT NOT NULL,
+                    voided INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // 任务模板，duration 为计时器时长（毫秒），description 包括 #标签 与 @项目
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS task_templates (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT UNIQUE NOT NULL,
+                    duration INTEGER,
+                    description TEXT
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    // 当表中缺少某个字段时补上，用于兼容旧版本创建的数据库
+    fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .flatten()
+            .any(|name| name == column);
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
+    // 旧版本的数据库没有全文索引，记录数不一致时重建
+    fn ensure_description_index(conn: &Connection) -> Result<()> {
+        let indexed: u64 = conn.query_row(
+            "SELECT COUNT(*) FROM time_slice_descriptions_fts",
+            [],
+            |row| row.get(0),
+        )?;
+        let total: u64 = conn.query_row(
+            "SELECT COUNT(*) FROM time_slice_descriptions d
+             JOIN time_slices t ON t.id = d.time_slice_id",
+            [],
+            |row| row.get(0),
+        )?;
+        if indexed == total {
+            return Ok(());
+        }
+
+        conn.execute("DELETE FROM time_slice_descriptions_fts", [])?;
+        let mut stmt = conn.prepare(
+            "SELECT d.time_slice_id, d.description FROM time_slice_descriptions d
+             JOIN time_slices t ON t.id = d.time_slice_id",
+        )?;
+        let descriptions = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, u64>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .filter_map(|result| result.ok());
+        for (time_slice_id, description) in descriptions {
+            conn.execute(
+                "INSERT INTO time_slice_descriptions_fts (rowid, description) VALUES (?1, ?2)",
+                (time_slice_id, segment_cjk(&description.unwrap_or_default())),
+            )?;
+        }
         Ok(())
     }
 
     pub fn get_all_time_slices(&self) -> Result<Vec<TimeSlice>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, start_time, end_time FROM time_slices")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, start_time, end_time, billable, invoice_id, time_zone FROM time_slices",
+        )?;
         let time_slices = stmt
             .query_map([], |row| {
                 Ok(TimeSlice {
                     id: row.get(0)?,
                     start_time: row.get(1)?,
                     end_time: row.get(2)?,
+                    billable: row.get(3)?,
+                    invoice_id: row.get(4)?,
+                    time_zone: row.get(5)?,
                 })
             })?
             .filter_map(|result| result.ok())
@@ -76,14 +289,103 @@ impl Database {
         Ok(time_slices)
     }
 
+    pub fn get_time_slice(&self, time_slice_id: u64) -> Result<Option<TimeSlice>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, start_time, end_time, billable, invoice_id, time_zone FROM time_slices WHERE id = ?1",
+        )?;
+        let time_slice = stmt
+            .query_map([time_slice_id], |row| {
+                Ok(TimeSlice {
+                    id: row.get(0)?,
+                    start_time: row.get(1)?,
+                    end_time: row.get(2)?,
+                    billable: row.get(3)?,
+                    invoice_id: row.get(4)?,
+                    time_zone: row.get(5)?,
+                })
+            })?
+            .next()
+            .transpose()?;
+        Ok(time_slice)
+    }
+
+    // 最近一条已结束记录的结束时间，供空闲提醒使用
+    pub fn get_last_end_time(&self) -> Result<Option<u64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT MAX(end_time) FROM time_slices", [], |row| {
+                row.get(0)
+            })?)
+    }
+
+    // 正在计时的切片，只读取这一条记录，供状态栏等频繁调用的场景使用
+    pub fn get_open_time_slice(&self) -> Result<Option<OpenTimeSlice>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.start_time, t.billable, t.invoice_id, t.time_zone, t.planned_end, d.description
+             FROM time_slices t
+             LEFT JOIN time_slice_descriptions d ON d.time_slice_id = t.id
+             WHERE t.end_time IS NULL
+             ORDER BY t.start_time DESC
+             LIMIT 1",
+        )?;
+        let open = stmt
+            .query_map([], |row| {
+                Ok(OpenTimeSlice {
+                    time_slice: TimeSlice {
+                        id: row.get(0)?,
+                        start_time: row.get(1)?,
+                        end_time: None,
+                        billable: row.get(2)?,
+                        invoice_id: row.get(3)?,
+                        time_zone: row.get(4)?,
+                    },
+                    planned_end: row.get(5)?,
+                    desc: row.get(6)?,
+                })
+            })?
+            .next()
+            .transpose()?;
+        Ok(open)
+    }
+
+    pub fn set_planned_end(&mut self, time_slice_id: u64, planned_end: Option<u64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE time_slices SET planned_end = ?1 WHERE id = ?2",
+            (planned_end, time_slice_id),
+        )?;
+        Ok(())
+    }
+
+    // 停止计时，返回 false 表示该切片已不存在或已经结束
+    pub fn finish_time_slice(&mut self, time_slice_id: u64, end: u64) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE time_slices SET end_time = ?1, planned_end = NULL WHERE id = ?2 AND end_time IS NULL",
+            (end, time_slice_id),
+        )?;
+        Ok(updated > 0)
+    }
+
+    // 重新开始计时，已开票的记录不能重新打开，返回 false 表示该切片不存在、仍在计时或已开票
+    pub fn reopen_time_slice(&mut self, time_slice_id: u64) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE time_slices SET end_time = NULL, planned_end = NULL
+             WHERE id = ?1 AND end_time IS NOT NULL AND invoice_id IS NULL",
+            [time_slice_id],
+        )?;
+        Ok(updated > 0)
+    }
+
     pub fn get_all_tags(&self) -> Result<Vec<Tag>> {
-        let mut stmt = self.conn.prepare("SELECT id, name, color FROM tags")?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, color, parent_id FROM tags")?;
         let tags = stmt
             .query_map([], |row| {
                 Ok(Tag {
                     id: row.get(0)?,
                     name: row.get(1)?,
                     color: row.get(2)?,
+                    parent_id: row.get(3)?,
                 })
             })?
             .filter_map(|result| result.ok())
@@ -91,6 +393,13 @@ impl Database {
         Ok(tags)
     }
 
+    // color 为 None 时清除标签的颜色
+    pub fn set_tag_color(&self, id: u64, color: Option<&str>) -> Result<()> {
+        self.conn
+            .execute("UPDATE tags SET color = ?1 WHERE id = ?2", (color, id))?;
+        Ok(())
+    }
+
     pub fn get_all_descriptions(&self) -> Result<Vec<Description>> {
         let mut stmt = self
             .conn
@@ -107,9 +416,42 @@ impl Database {
         Ok(descriptions)
     }
 
+    // 最近 limit 条记录的描述及其开始时间，最近的在前
+    pub fn get_recent_descriptions(&self, limit: usize) -> Result<Vec<(String, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.description, t.start_time
+             FROM time_slice_descriptions d
+             JOIN time_slices t ON t.id = d.time_slice_id
+             WHERE TRIM(d.description) != ''
+             ORDER BY t.start_time DESC
+             LIMIT ?1",
+        )?;
+        let descriptions = stmt
+            .query_map([limit as u64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(descriptions)
+    }
+
+    // 最近开始的 limit 条记录的 ID 与描述，最近的在前
+    pub fn get_recent_time_slice_ids(&self, limit: usize) -> Result<Vec<(u64, Option<String>)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, d.description
+             FROM time_slices t
+             LEFT JOIN time_slice_descriptions d ON d.time_slice_id = t.id
+             ORDER BY t.start_time DESC
+             LIMIT ?1",
+        )?;
+        let ids = stmt
+            .query_map([limit as u64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(ids)
+    }
+
     pub fn get_all_times_tag(&self) -> Result<HashMap<u64, Vec<Tag>>> {
         let mut stmt = self.conn.prepare(
-            "SELECT t.id, t.name, t.color, ts.time_slice_id
+            "SELECT t.id, t.name, t.color, t.parent_id, ts.time_slice_id
              FROM tags t
              JOIN time_slice_Tags ts ON t.id = ts.tag_id",
         )?;
@@ -122,43 +464,69 @@ impl Database {
                     id: row.get(0)?,
                     name: row.get(1)?,
                     color: row.get(2)?,
+                    parent_id: row.get(3)?,
                 },
-                row.get::<_, u64>(3)?,
+                row.get::<_, u64>(4)?,
             ))
         })?;
 
-        for row in rows {
-            if let Ok((tag, time_slice_id)) = row {
-                time_slice_tags
-                    .entry(time_slice_id)
-                    .or_insert_with(Vec::new)
-                    .push(tag);
-            }
+        for (tag, time_slice_id) in rows.flatten() {
+            time_slice_tags.entry(time_slice_id).or_default().push(tag);
         }
 
         Ok(time_slice_tags)
     }
 
-    pub fn insert_time_slice(&mut self, start: u64, end: Option<u64>) -> Result<u64> {
+    // time_zone 为记录时所在的时区
+    pub fn insert_time_slice(
+        &mut self,
+        start: u64,
+        end: Option<u64>,
+        time_zone: &str,
+    ) -> Result<u64> {
         self.conn.execute(
-            "INSERT INTO time_slices (start_time, end_time) VALUES (?1, ?2)",
-            (start, end),
+            "INSERT INTO time_slices (start_time, end_time, time_zone) VALUES (?1, ?2, ?3)",
+            (start, end, time_zone),
         )?;
         Ok(self.conn.last_insert_rowid() as u64)
     }
 
+    // 标签支持层级，例如 client/acme/backend，会依次创建 client、client/acme 并记录父子关系
     pub fn find_or_create_tag(&self, name: &str) -> Result<Tag, rusqlite::Error> {
-        self.conn
-            .execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [name])?;
+        let mut tag = None;
+        for path in tag_ancestors(name) {
+            let parent_id = tag.as_ref().map(|t: &Tag| t.id);
+            tag = Some(self.find_or_create_single_tag(&path, parent_id)?);
+        }
+        tag.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    fn find_or_create_single_tag(
+        &self,
+        name: &str,
+        parent_id: Option<u64>,
+    ) -> Result<Tag, rusqlite::Error> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tags (name, parent_id) VALUES (?1, ?2)",
+            (name, parent_id),
+        )?;
+        // 旧数据中的标签可能没有记录父标签
+        if parent_id.is_some() {
+            self.conn.execute(
+                "UPDATE tags SET parent_id = ?1 WHERE name = ?2 AND parent_id IS NULL",
+                (parent_id, name),
+            )?;
+        }
         let mut stmt = self
             .conn
-            .prepare("SELECT id, name FROM tags WHERE name = ?1")?;
+            .prepare("SELECT id, name, color, parent_id FROM tags WHERE name = ?1")?;
 
         let tag = stmt.query_row([name], |row| {
             Ok(Tag {
                 id: row.get(0)?,
                 name: row.get(1)?,
-                color: None,
+                color: row.get(2)?,
+                parent_id: row.get(3)?,
             })
         })?;
 
@@ -184,6 +552,7 @@ impl Database {
             "INSERT INTO time_slice_descriptions (time_slice_id, description) VALUES (?1,?2)",
         )?;
         stmt.execute((time_slice_id, description))?;
+        self.index_description(time_slice_id, description)?;
         Ok(())
     }
 
@@ -193,9 +562,11 @@ impl Database {
         end: Option<u64>,
         tags: &Vec<String>,
         desc: &Option<String>,
-    ) -> Result<()> {
+        project: &Option<String>,
+        time_zone: &str,
+    ) -> Result<u64> {
         // 插入时间片段
-        let time_slice_id = self.insert_time_slice(start, end)?;
+        let time_slice_id = self.insert_time_slice(start, end, time_zone)?;
 
         if let Some(desc_str) = desc {
             // 写入时间片段描述
@@ -205,7 +576,7 @@ impl Database {
             if !tags.is_empty() {
                 let mut tag_ids = vec![];
                 for name in tags {
-                    let tag_result = self.find_or_create_tag(&name);
+                    let tag_result = self.find_or_create_tag(name);
                     match tag_result {
                         Ok(tag) => tag_ids.push(tag.id),
                         Err(e) => println!("Error handling tag '{}': {}", name, e),
@@ -218,7 +589,44 @@ impl Database {
             }
         }
 
-        Ok(())
+        // 写入时间片段所属项目
+        if let Some(name) = project {
+            let project = self.find_or_create_project(name)?;
+            self.set_time_slice_project(time_slice_id, Some(project.id))?;
+        }
+
+        Ok(time_slice_id)
+    }
+
+    // 在保存点中执行多条修改，任一步失败时全部撤销
+    pub fn atomically<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.conn.execute_batch("SAVEPOINT atomically")?;
+        let result = f(self);
+        match result {
+            Ok(_) => self.conn.execute_batch("RELEASE atomically")?,
+            Err(_) => self
+                .conn
+                .execute_batch("ROLLBACK TO atomically; RELEASE atomically")?,
+        }
+        result
+    }
+
+    // 开始计时：写入没有结束时间的记录及其预定结束时间，任一步失败时不留下运行中的记录
+    pub fn insert_open_time_slice(
+        &mut self,
+        start: u64,
+        tags: &Vec<String>,
+        desc: &Option<String>,
+        project: &Option<String>,
+        time_zone: &str,
+        planned_end: Option<u64>,
+    ) -> Result<u64> {
+        self.atomically(|db| {
+            let time_slice_id =
+                db.insert_time_slice_info(start, None, tags, desc, project, time_zone)?;
+            db.set_planned_end(time_slice_id, planned_end)?;
+            Ok(time_slice_id)
+        })
     }
 
     pub fn remove_time_slice(&mut self, time_slice_id: u64) -> Result<()> {
@@ -229,12 +637,28 @@ impl Database {
 
     pub fn update_time_slice(&mut self, time_slice: &TimeSlice) -> Result<()> {
         self.conn.execute(
-            "UPDATE time_slices SET start_time =?1, end_time =?2 WHERE id =?3",
-            (time_slice.start_time, time_slice.end_time, time_slice.id),
+            "UPDATE time_slices SET start_time =?1, end_time =?2, billable =?3 WHERE id =?4",
+            (
+                time_slice.start_time,
+                time_slice.end_time,
+                time_slice.billable,
+                time_slice.id,
+            ),
         )?;
         Ok(())
     }
 
+    pub fn set_time_slice_billable(&mut self, time_slice_id: u64, billable: bool) -> Result<()> {
+        let changed = self.conn.execute(
+            "UPDATE time_slices SET billable = ?1 WHERE id = ?2",
+            (billable, time_slice_id),
+        )?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("Time slice {time_slice_id} not found"));
+        }
+        Ok(())
+    }
+
     pub fn update_time_slice_tags(&mut self, time_slice_id: u64, tags: &Vec<String>) -> Result<()> {
         self.conn.execute(
             "DELETE FROM time_slice_Tags WHERE time_slice_id =?1",
@@ -261,10 +685,543 @@ impl Database {
         time_slice_id: u64,
         description: &str,
     ) -> Result<()> {
+        // 没有描述的记录同样可以补充描述
+        self.conn.execute(
+            "INSERT INTO time_slice_descriptions (time_slice_id, description) VALUES (?1, ?2)
+             ON CONFLICT (time_slice_id) DO UPDATE SET description = excluded.description",
+            (time_slice_id, description),
+        )?;
+        self.index_description(time_slice_id, description)?;
+        Ok(())
+    }
+
+    // 写入或替换一条描述的全文索引
+    fn index_description(&self, time_slice_id: u64, description: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM time_slice_descriptions_fts WHERE rowid = ?1",
+            [time_slice_id],
+        )?;
         self.conn.execute(
-            "UPDATE time_slice_descriptions SET description =?1 WHERE time_slice_id =?2",
-            (description, time_slice_id),
+            "INSERT INTO time_slice_descriptions_fts (rowid, description) VALUES (?1, ?2)",
+            (time_slice_id, segment_cjk(description)),
+        )?;
+        Ok(())
+    }
+
+    // 全文搜索描述，支持 FTS5 的短语、前缀与布尔查询，结果按相关度排序
+    pub fn search_descriptions(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT rowid, snippet(time_slice_descriptions_fts, 0, ?2, ?3, '…', 24), rank
+             FROM time_slice_descriptions_fts
+             WHERE time_slice_descriptions_fts MATCH ?1
+             ORDER BY rank",
         )?;
+        let hits = stmt
+            .query_map(
+                (
+                    segment_cjk(query),
+                    HIGHLIGHT_START.to_string(),
+                    HIGHLIGHT_END.to_string(),
+                ),
+                |row| {
+                    Ok(SearchHit {
+                        time_slice_id: row.get(0)?,
+                        snippet: desegment_cjk(&row.get::<_, String>(1)?),
+                        rank: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<SearchHit>, _>>()
+            .map_err(|e| anyhow::anyhow!("Invalid search query '{query}': {e}"))?;
+        Ok(hits)
+    }
+
+    fn row_to_project(row: &rusqlite::Row) -> rusqlite::Result<Project> {
+        Ok(Project {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            client_id: row.get(2)?,
+            client: row.get(3)?,
+            archived: row.get(4)?,
+        })
+    }
+
+    pub fn get_all_projects(&self) -> Result<Vec<Project>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.client_id, c.name, p.archived
+             FROM projects p
+             LEFT JOIN clients c ON p.client_id = c.id
+             ORDER BY p.name",
+        )?;
+        let projects = stmt
+            .query_map([], Self::row_to_project)?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(projects)
+    }
+
+    pub fn find_project(&self, name: &str) -> Result<Option<Project>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.client_id, c.name, p.archived
+             FROM projects p
+             LEFT JOIN clients c ON p.client_id = c.id
+             WHERE p.name = ?1",
+        )?;
+        let mut rows = stmt.query_map([name], Self::row_to_project)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    pub fn find_or_create_project(&self, name: &str) -> Result<Project> {
+        self.conn
+            .execute("INSERT OR IGNORE INTO projects (name) VALUES (?1)", [name])?;
+        self.find_project(name)?
+            .ok_or_else(|| anyhow::anyhow!("Project '{name}' not found"))
+    }
+
+    pub fn create_project(&self, name: &str, client_id: Option<u64>) -> Result<Project> {
+        self.conn.execute(
+            "INSERT INTO projects (name, client_id) VALUES (?1, ?2)",
+            (name, client_id),
+        )?;
+        self.find_project(name)?
+            .ok_or_else(|| anyhow::anyhow!("Project '{name}' not found"))
+    }
+
+    pub fn rename_project(&self, id: u64, name: &str) -> Result<()> {
+        self.conn
+            .execute("UPDATE projects SET name = ?1 WHERE id = ?2", (name, id))?;
+        Ok(())
+    }
+
+    pub fn set_project_client(&self, id: u64, client_id: Option<u64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET client_id = ?1 WHERE id = ?2",
+            (client_id, id),
+        )?;
+        Ok(())
+    }
+
+    pub fn set_project_archived(&self, id: u64, archived: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET archived = ?1 WHERE id = ?2",
+            (archived, id),
+        )?;
+        Ok(())
+    }
+
+    // 删除项目时一并删除时间切片与项目的关联，时间切片本身保留
+    pub fn remove_project(&self, id: u64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM time_slice_projects WHERE project_id = ?1",
+            [id],
+        )?;
+        self.conn
+            .execute("DELETE FROM projects WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    // 获取所有时间切片对应的项目，key 为时间切片 ID
+    pub fn get_all_times_project(&self) -> Result<HashMap<u64, Project>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.client_id, c.name, p.archived, tp.time_slice_id
+             FROM time_slice_projects tp
+             JOIN projects p ON tp.project_id = p.id
+             LEFT JOIN clients c ON p.client_id = c.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((Self::row_to_project(row)?, row.get::<_, u64>(5)?))
+        })?;
+        let mut time_slice_projects = HashMap::new();
+        for (project, time_slice_id) in rows.flatten() {
+            time_slice_projects.insert(time_slice_id, project);
+        }
+        Ok(time_slice_projects)
+    }
+
+    pub fn set_time_slice_project(
+        &mut self,
+        time_slice_id: u64,
+        project_id: Option<u64>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM time_slice_projects WHERE time_slice_id = ?1",
+            [time_slice_id],
+        )?;
+        if let Some(project_id) = project_id {
+            self.conn.execute(
+                "INSERT INTO time_slice_projects (time_slice_id, project_id) VALUES (?1, ?2)",
+                [time_slice_id, project_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn row_to_client(row: &rusqlite::Row) -> rusqlite::Result<Client> {
+        Ok(Client {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            archived: row.get(2)?,
+        })
+    }
+
+    pub fn get_all_clients(&self) -> Result<Vec<Client>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, archived FROM clients ORDER BY name")?;
+        let clients = stmt
+            .query_map([], Self::row_to_client)?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(clients)
+    }
+
+    pub fn find_client(&self, name: &str) -> Result<Option<Client>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, archived FROM clients WHERE name = ?1")?;
+        let mut rows = stmt.query_map([name], Self::row_to_client)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    pub fn find_or_create_client(&self, name: &str) -> Result<Client> {
+        self.conn
+            .execute("INSERT OR IGNORE INTO clients (name) VALUES (?1)", [name])?;
+        self.find_client(name)?
+            .ok_or_else(|| anyhow::anyhow!("Client '{name}' not found"))
+    }
+
+    pub fn rename_client(&self, id: u64, name: &str) -> Result<()> {
+        self.conn
+            .execute("UPDATE clients SET name = ?1 WHERE id = ?2", (name, id))?;
+        Ok(())
+    }
+
+    pub fn set_client_archived(&self, id: u64, archived: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE clients SET archived = ?1 WHERE id = ?2",
+            (archived, id),
+        )?;
+        Ok(())
+    }
+
+    // 删除客户时，其下项目不再归属任何客户
+    pub fn remove_client(&self, id: u64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET client_id = NULL WHERE client_id = ?1",
+            [id],
+        )?;
+        self.conn
+            .execute("DELETE FROM clients WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    pub fn get_all_rates(&self) -> Result<Vec<Rate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.id, r.target_type, r.target_id, r.hourly_rate, r.effective_from,
+                    COALESCE(p.name, c.name, t.name, '')
+             FROM rates r
+             LEFT JOIN projects p ON r.target_type = 'project' AND r.target_id = p.id
+             LEFT JOIN clients c ON r.target_type = 'client' AND r.target_id = c.id
+             LEFT JOIN tags t ON r.target_type = 'tag' AND r.target_id = t.id
+             ORDER BY r.target_type, r.target_id, r.effective_from",
+        )?;
+        let rates = stmt
+            .query_map([], |row| {
+                let target: String = row.get(1)?;
+                Ok(Rate {
+                    id: row.get(0)?,
+                    target: target.parse().map_err(|e: String| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            1,
+                            rusqlite::types::Type::Text,
+                            e.into(),
+                        )
+                    })?,
+                    target_id: row.get(2)?,
+                    hourly_rate: row.get(3)?,
+                    effective_from: row.get(4)?,
+                    target_name: row.get(5)?,
+                })
+            })?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(rates)
+    }
+
+    // 同一对象同一生效时间只保留一条费率
+    pub fn set_rate(
+        &mut self,
+        target: RateTarget,
+        target_id: u64,
+        hourly_rate: f64,
+        effective_from: u64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO rates (target_type, target_id, hourly_rate, effective_from)
+             VALUES (?1, ?2, ?3, ?4)",
+            (target.as_str(), target_id, hourly_rate, effective_from),
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_rate(&mut self, id: u64) -> Result<()> {
+        let changed = self.conn.execute("DELETE FROM rates WHERE id = ?1", [id])?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("Rate {id} not found"));
+        }
+        Ok(())
+    }
+
+    fn row_to_invoice(row: &rusqlite::Row) -> rusqlite::Result<Invoice> {
+        Ok(Invoice {
+            id: row.get(0)?,
+            number: invoice_number(row.get(1)?),
+            client: row.get(2)?,
+            project: row.get(3)?,
+            start_time: row.get(4)?,
+            end_time: row.get(5)?,
+            created_at: row.get(6)?,
+            total: row.get(7)?,
+            currency: row.get(8)?,
+            voided: row.get(9)?,
+        })
+    }
+
+    pub fn get_all_invoices(&self) -> Result<Vec<Invoice>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, seq, client, project, start_time, end_time, created_at, total, currency, voided
+             FROM invoices ORDER BY seq",
+        )?;
+        let invoices = stmt
+            .query_map([], Self::row_to_invoice)?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(invoices)
+    }
+
+    // 下一张发票的编号
+    pub fn next_invoice_number(&self) -> Result<String> {
+        let seq: u64 = self.conn.query_row(
+            "SELECT COALESCE(MAX(seq), 0) + 1 FROM invoices",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(invoice_number(seq))
+    }
+
+    // 创建发票并将时间切片标记为已开票，在同一个事务中完成
+    // before_commit 在提交前调用，例如写入发票文件，返回错误时整个事务回滚
+    pub fn create_invoice(
+        &mut self,
+        invoice: &Invoice,
+        time_slice_ids: &[u64],
+        before_commit: impl FnOnce(&Invoice) -> Result<()>,
+    ) -> Result<Invoice> {
+        let tx = self.conn.transaction()?;
+        let seq: u64 = tx.query_row(
+            "SELECT COALESCE(MAX(seq), 0) + 1 FROM invoices",
+            [],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO invoices (seq, client, project, start_time, end_time, created_at, total, currency)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            (
+                seq,
+                &invoice.client,
+                &invoice.project,
+                invoice.start_time,
+                invoice.end_time,
+                invoice.created_at,
+                invoice.total,
+                &invoice.currency,
+            ),
+        )?;
+        let id = tx.last_insert_rowid() as u64;
+        let mut updated = 0;
+        for time_slice_id in time_slice_ids {
+            updated += tx.execute(
+                "UPDATE time_slices SET invoice_id = ?1 WHERE id = ?2 AND invoice_id IS NULL",
+                [id, *time_slice_id],
+            )?;
+        }
+        // 部分时间切片已被其它发票包含时金额不再准确，放弃整张发票
+        if updated != time_slice_ids.len() {
+            return Err(anyhow::anyhow!(
+                "Some time records have been invoiced already, nothing was recorded."
+            ));
+        }
+        let invoice = Invoice {
+            id,
+            number: invoice_number(seq),
+            ..invoice.clone()
+        };
+        before_commit(&invoice)?;
+        tx.commit()?;
+        Ok(invoice)
+    }
+
+    // 作废发票，其包含的时间切片可以重新开票
+    pub fn void_invoice(&mut self, id: u64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("UPDATE invoices SET voided = 1 WHERE id = ?1", [id])?;
+        tx.execute(
+            "UPDATE time_slices SET invoice_id = NULL WHERE invoice_id = ?1",
+            [id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_all_goals(&self) -> Result<Vec<Goal>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, tag, period, kind, target FROM goals ORDER BY id")?;
+        let goals = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, u64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, u64>(4)?,
+                ))
+            })?
+            .filter_map(|result| result.ok())
+            .filter_map(|(id, tag, period, kind, target)| {
+                Some(Goal {
+                    id,
+                    tag,
+                    period: period.parse::<GoalPeriod>().ok()?,
+                    kind: kind.parse::<GoalKind>().ok()?,
+                    target,
+                })
+            })
+            .collect();
+        Ok(goals)
+    }
+
+    pub fn insert_goal(&mut self, goal: &Goal) -> Result<u64> {
+        self.conn.execute(
+            "INSERT INTO goals (tag, period, kind, target) VALUES (?1, ?2, ?3, ?4)",
+            (
+                &goal.tag,
+                goal.period.as_str(),
+                goal.kind.as_str(),
+                goal.target,
+            ),
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+
+    pub fn remove_goal(&mut self, id: u64) -> Result<()> {
+        let changed = self.conn.execute("DELETE FROM goals WHERE id = ?1", [id])?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("Goal {id} not found"));
+        }
+        Ok(())
+    }
+
+    pub fn get_all_budgets(&self) -> Result<Vec<Budget>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT b.id, b.target_type, b.target_id, COALESCE(p.name, t.name, ''), b.period, b.amount
+             FROM budgets b
+             LEFT JOIN projects p ON b.target_type = 'project' AND b.target_id = p.id
+             LEFT JOIN tags t ON b.target_type = 'tag' AND b.target_id = t.id
+             ORDER BY b.id",
+        )?;
+        let budgets = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, u64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u64>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, u64>(5)?,
+                ))
+            })?
+            .filter_map(|result| result.ok())
+            .filter_map(|(id, target, target_id, target_name, period, amount)| {
+                Some(Budget {
+                    id,
+                    target: target.parse::<BudgetTarget>().ok()?,
+                    target_id,
+                    target_name,
+                    amount,
+                    period: period.parse::<BudgetPeriod>().ok()?,
+                })
+            })
+            .collect();
+        Ok(budgets)
+    }
+
+    // 同一对象同一周期只保留一条预算
+    pub fn set_budget(
+        &mut self,
+        target: BudgetTarget,
+        target_id: u64,
+        period: BudgetPeriod,
+        amount: u64,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO budgets (target_type, target_id, period, amount)
+             VALUES (?1, ?2, ?3, ?4)",
+            (target.as_str(), target_id, period.as_str(), amount),
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_budget(&mut self, id: u64) -> Result<()> {
+        let changed = self
+            .conn
+            .execute("DELETE FROM budgets WHERE id = ?1", [id])?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("Budget {id} not found"));
+        }
+        Ok(())
+    }
+
+    pub fn get_all_task_templates(&self) -> Result<Vec<TaskTemplate>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, duration, description FROM task_templates ORDER BY name")?;
+        let templates = stmt
+            .query_map([], |row| {
+                Ok(TaskTemplate {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    duration: row.get(2)?,
+                    desc: row.get(3)?,
+                })
+            })?
+            .filter_map(|result| result.ok())
+            .collect();
+        Ok(templates)
+    }
+
+    // 同名的模板只保留一条，再次添加时覆盖
+    pub fn set_task_template(
+        &mut self,
+        name: &str,
+        duration: Option<u64>,
+        desc: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO task_templates (name, duration, description) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET duration = excluded.duration, description = excluded.description",
+            (name, duration, desc),
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_task_template(&mut self, name: &str) -> Result<()> {
+        let changed = self
+            .conn
+            .execute("DELETE FROM task_templates WHERE name = ?1", [name])?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("Template '{name}' not found"));
+        }
         Ok(())
     }
 }
@@ -7,6 +7,7 @@ pub struct Description {
 }
 
 impl Description {
+    #[allow(dead_code)]
     pub fn new(time_slice_id: u64, desc: &str) -> Self {
         Self {
             time_slice_id,
@@ -0,0 +1,349 @@
+use super::calendar::{Calendar, CalendarPeriod};
+use super::tag::is_in_tag_subtree;
+use super::timeline::Timeline;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalPeriod {
+    Day,
+    Week,
+}
+
+impl GoalPeriod {
+    pub fn calendar_period(&self) -> CalendarPeriod {
+        match self {
+            GoalPeriod::Day => CalendarPeriod::Day,
+            GoalPeriod::Week => CalendarPeriod::Week,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalPeriod::Day => "day",
+            GoalPeriod::Week => "week",
+        }
+    }
+}
+
+impl FromStr for GoalPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" | "daily" => Ok(GoalPeriod::Day),
+            "week" | "weekly" => Ok(GoalPeriod::Week),
+            _ => Err(format!(
+                "Invalid goal period: '{s}'. Expected 'day' or 'week'"
+            )),
+        }
+    }
+}
+
+// min 表示至少达到目标时长，max 表示不超过目标时长
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalKind {
+    Min,
+    Max,
+}
+
+impl GoalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalKind::Min => "min",
+            GoalKind::Max => "max",
+        }
+    }
+}
+
+impl FromStr for GoalKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "min" => Ok(GoalKind::Min),
+            "max" => Ok(GoalKind::Max),
+            _ => Err(format!("Invalid goal kind: '{s}'. Expected 'min' or 'max'")),
+        }
+    }
+}
+
+// 目标，例如 "20h #deepwork per week" 或 "max 2h #meeting per day"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: u64,
+    // 标签路径，子标签的时间同样计入
+    pub tag: String,
+    pub period: GoalPeriod,
+    pub kind: GoalKind,
+    // 目标时长（毫秒）
+    pub target: u64,
+}
+
+// 目标在某个周期内的完成情况
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub period_start: NaiveDate,
+    pub done: u64,
+    pub target: u64,
+    pub kind: GoalKind,
+}
+
+impl GoalProgress {
+    // 周期结束时是否达成目标
+    pub fn is_met(&self) -> bool {
+        match self.kind {
+            GoalKind::Min => self.done >= self.target,
+            GoalKind::Max => self.done <= self.target,
+        }
+    }
+
+    // 完成比例，可能超过 1
+    pub fn ratio(&self) -> f64 {
+        if self.target == 0 {
+            return 1.0;
+        }
+        self.done as f64 / self.target as f64
+    }
+}
+
+// 目标在多个周期中的表现
+#[derive(Debug, Clone)]
+pub struct GoalHistory {
+    // 按时间先后排列，最后一个为当前周期
+    pub periods: Vec<GoalProgress>,
+    // 截至上一个完整周期（当前周期已达成时包含当前周期）连续达成的周期数
+    pub current_streak: usize,
+    pub best_streak: usize,
+    // 已结束周期中未达成的次数
+    pub misses: usize,
+}
+
+impl Goal {
+    // 包含 date 的周期的第一天
+    pub fn period_start(&self, date: NaiveDate, calendar: &Calendar) -> NaiveDate {
+        calendar.period_start(date, self.period.calendar_period())
+    }
+
+    fn next_period_start(&self, start: NaiveDate) -> NaiveDate {
+        match self.period {
+            GoalPeriod::Day => start + Duration::days(1),
+            GoalPeriod::Week => start + Duration::days(7),
+        }
+    }
+
+    // 计算从 start 开始的周期内，该目标标签下记录的时长
+    // extra 为额外计入的时长，例如正在运行的计时器
+    pub fn progress(
+        &self,
+        timeline: &Timeline,
+        start: NaiveDate,
+        extra: u64,
+        calendar: &Calendar,
+    ) -> GoalProgress {
+        let next = self.next_period_start(start);
+        let mut done = extra;
+        for slice in &timeline.list {
+            let matched = timeline.tags.get(&slice.id).is_some_and(|tags| {
+                tags.iter()
+                    .any(|tag| is_in_tag_subtree(&tag.name, &self.tag))
+            });
+            if !matched {
+                continue;
+            }
+            let end = slice.end_time.unwrap_or(slice.start_time);
+            done += calendar
+                .split_by_day(slice.start_time, end)
+                .into_iter()
+                .filter(|(date, _)| *date >= start && *date < next)
+                .map(|(_, len)| len)
+                .sum::<u64>();
+        }
+        GoalProgress {
+            period_start: start,
+            done,
+            target: self.target,
+            kind: self.kind,
+        }
+    }
+
+    // 计算截至 today 的最近 count 个周期的表现
+    pub fn history(
+        &self,
+        timeline: &Timeline,
+        today: NaiveDate,
+        count: usize,
+        calendar: &Calendar,
+    ) -> GoalHistory {
+        let mut starts = vec![self.period_start(today, calendar)];
+        while starts.len() < count.max(1) {
+            let last = *starts.last().expect("starts is not empty");
+            starts.push(self.period_start(last - Duration::days(1), calendar));
+        }
+        starts.reverse();
+
+        let periods: Vec<GoalProgress> = starts
+            .into_iter()
+            .map(|start| self.progress(timeline, start, 0, calendar))
+            .collect();
+
+        let (current, finished) = periods.split_last().expect("periods is not empty");
+        let mut best_streak = 0;
+        let mut streak = 0;
+        let mut misses = 0;
+        for progress in finished {
+            if progress.is_met() {
+                streak += 1;
+                best_streak = best_streak.max(streak);
+            } else {
+                streak = 0;
+                misses += 1;
+            }
+        }
+        // 当前周期尚未结束，只有 min 目标已经达成时才计入连续记录
+        let mut current_streak = streak;
+        if current.kind == GoalKind::Min && current.is_met() {
+            current_streak += 1;
+            best_streak = best_streak.max(current_streak);
+        }
+
+        GoalHistory {
+            periods,
+            current_streak,
+            best_streak,
+            misses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{tag::Tag, time_slice::TimeSlice};
+    use chrono::Weekday;
+    use chrono_tz::Tz;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn calendar() -> Calendar {
+        Calendar {
+            time_zone: Tz::UTC,
+            week_start: Weekday::Mon,
+            day_offset: 0,
+            split_days: true,
+        }
+    }
+
+    // 2025-02-day，2025-02-10 为星期一
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 2, day).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> u64 {
+        date(day)
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp_millis() as u64
+    }
+
+    // 每项为 (日期, 开始的小时, 时长（小时）, 标签)
+    fn timeline(slices: &[(u32, u32, u64, &str)]) -> Timeline {
+        let mut timeline = Timeline::new();
+        for (id, &(day, hour, hours, tag)) in slices.iter().enumerate() {
+            let id = id as u64 + 1;
+            let start = at(day, hour);
+            timeline.push(TimeSlice::new(id, start, Some(start + hours * HOUR)));
+            timeline.tags.insert(id, vec![Tag::new(tag, None)]);
+        }
+        timeline
+    }
+
+    fn goal(kind: GoalKind, hours: u64, period: GoalPeriod) -> Goal {
+        Goal {
+            id: 1,
+            tag: "deepwork".to_string(),
+            period,
+            kind,
+            target: hours * HOUR,
+        }
+    }
+
+    #[test]
+    fn progress_counts_the_tag_subtree_within_the_period() {
+        let timeline = timeline(&[
+            (10, 9, 2, "deepwork"),
+            (11, 9, 3, "deepwork/rust"),
+            (11, 14, 4, "meeting"),
+            // 跨越周日午夜的记录只计入本周的部分
+            (9, 22, 4, "deepwork"),
+            (17, 9, 5, "deepwork"),
+        ]);
+        let goal = goal(GoalKind::Min, 10, GoalPeriod::Week);
+        let start = goal.period_start(date(12), &calendar());
+        assert_eq!(start, date(10));
+        let progress = goal.progress(&timeline, start, HOUR, &calendar());
+        // 2 + 3 + 2（跨天记录在本周的部分）+ 1（额外计入的时长）
+        assert_eq!(progress.done, 8 * HOUR);
+        assert!(!progress.is_met());
+        assert_eq!(progress.ratio(), 0.8);
+    }
+
+    #[test]
+    fn history_counts_streaks_and_misses() {
+        // 每天至少 2 小时：10、11 日达成，12 日未达成，13、14 日达成，15 日（今天）进行中
+        let timeline = timeline(&[
+            (10, 9, 2, "deepwork"),
+            (11, 9, 3, "deepwork"),
+            (12, 9, 1, "deepwork"),
+            (13, 9, 2, "deepwork"),
+            (14, 9, 2, "deepwork"),
+            (15, 9, 1, "deepwork"),
+        ]);
+        let goal = goal(GoalKind::Min, 2, GoalPeriod::Day);
+        let history = goal.history(&timeline, date(15), 6, &calendar());
+        let starts: Vec<NaiveDate> = history.periods.iter().map(|p| p.period_start).collect();
+        assert_eq!(starts, (10..=15).map(date).collect::<Vec<_>>());
+        assert_eq!(history.current_streak, 2);
+        assert_eq!(history.best_streak, 2);
+        assert_eq!(history.misses, 1);
+
+        // 当前周期达成后计入连续记录
+        let timeline_met = {
+            let mut timeline = timeline;
+            timeline.push(TimeSlice::new(7, at(15, 12), Some(at(15, 13))));
+            timeline.tags.insert(7, vec![Tag::new("deepwork", None)]);
+            timeline
+        };
+        let history = goal.history(&timeline_met, date(15), 6, &calendar());
+        assert_eq!(history.current_streak, 3);
+        assert_eq!(history.best_streak, 3);
+        assert_eq!(history.misses, 1);
+    }
+
+    #[test]
+    fn max_goal_current_period_is_not_counted_until_it_ends() {
+        // 每天最多 2 小时：13、14 日没有超出，15 日（今天）暂时也没有超出
+        let timeline = timeline(&[
+            (12, 9, 3, "deepwork"),
+            (13, 9, 1, "deepwork"),
+            (15, 9, 1, "deepwork"),
+        ]);
+        let goal = goal(GoalKind::Max, 2, GoalPeriod::Day);
+        let history = goal.history(&timeline, date(15), 4, &calendar());
+        assert!(history.periods[3].is_met());
+        assert_eq!(history.current_streak, 2);
+        assert_eq!(history.best_streak, 2);
+        assert_eq!(history.misses, 1);
+
+        // 当前周期已经超出时同样不计为未达成，等周期结束后再计算
+        let timeline = self::timeline(&[(15, 9, 3, "deepwork")]);
+        let history = goal.history(&timeline, date(15), 2, &calendar());
+        assert!(!history.periods[1].is_met());
+        assert_eq!(history.current_streak, 1);
+        assert_eq!(history.misses, 0);
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// 发票，开具后其包含的时间切片会被标记为已开票，避免重复计费
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invoice {
+    pub id: u64,
+    // 顺序编号，例如 INV-0001
+    pub number: String,
+    pub client: Option<String>,
+    pub project: Option<String>,
+    // 开票的时间范围
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub created_at: u64,
+    pub total: f64,
+    pub currency: String,
+    // 作废的发票会释放其包含的时间切片，但编号不会被重复使用
+    pub voided: bool,
+}
+
+// 发票明细的分组方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceGrouping {
+    Tag,
+    Day,
+    Description,
+}
+
+impl FromStr for InvoiceGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tag" => Ok(InvoiceGrouping::Tag),
+            "day" => Ok(InvoiceGrouping::Day),
+            "desc" | "description" => Ok(InvoiceGrouping::Description),
+            _ => Err(format!(
+                "Invalid invoice grouping: '{s}'. Expected 'tag', 'day' or 'desc'"
+            )),
+        }
+    }
+}
+
+pub fn invoice_number(seq: u64) -> String {
+    format!("INV-{:04}", seq)
+}
@@ -1,5 +1,20 @@
+pub mod budget;
+pub mod calendar;
+pub mod chart;
+pub mod client;
 pub mod database;
 pub mod description;
+pub mod goal;
+pub mod invoice;
+pub mod project;
+pub mod rate;
+pub mod recent;
+pub mod reminder;
+pub mod report;
+pub mod rounding;
+pub mod search;
+pub mod standup;
 pub mod tag;
+pub mod task_template;
 pub mod time_slice;
 pub mod timeline;
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+// 项目，时间切片可以关联到一个项目，项目可以归属于一个客户
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: u64,
+    pub name: String,
+    pub client_id: Option<u64>,
+    // 客户名称，查询时一并取出方便展示
+    pub client: Option<String>,
+    pub archived: bool,
+}
@@ -0,0 +1,343 @@
+use super::project::Project;
+use super::rounding::RoundingPolicy;
+use super::tag::{Tag, is_in_tag_subtree, tag_depth};
+use super::time_slice::TimeSlice;
+use super::timeline::Timeline;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+// 费率可以设置在项目、客户或标签上
+// 优先级：项目 > 标签（越具体的子标签越优先）> 客户
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateTarget {
+    Project,
+    Client,
+    Tag,
+}
+
+impl RateTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateTarget::Project => "project",
+            RateTarget::Client => "client",
+            RateTarget::Tag => "tag",
+        }
+    }
+}
+
+impl fmt::Display for RateTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RateTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "project" => Ok(RateTarget::Project),
+            "client" => Ok(RateTarget::Client),
+            "tag" => Ok(RateTarget::Tag),
+            _ => Err(format!(
+                "Invalid rate target: '{s}'. Expected 'project', 'client' or 'tag'"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rate {
+    pub id: u64,
+    pub target: RateTarget,
+    pub target_id: u64,
+    // 项目、客户或标签的名称，查询时一并取出方便展示
+    pub target_name: String,
+    // 每小时的金额
+    pub hourly_rate: f64,
+    // 生效时间（毫秒时间戳），之后开始的时间切片使用该费率
+    pub effective_from: u64,
+}
+
+// 所有费率的集合，用于计算时间切片适用的费率
+pub struct RateBook {
+    pub rates: Vec<Rate>,
+}
+
+// 按项目（没有项目时按第一个标签）汇总的收入
+#[derive(Debug, Default, Clone)]
+pub struct EarningsLine {
+    pub duration: u64,
+    // 按取整规则取整后的时长，没有取整规则时与 duration 相同
+    pub rounded: u64,
+    pub amount: f64,
+    // 当分组内费率唯一时记录下来用于展示
+    pub rate: Option<f64>,
+    // 计入该分组的时间切片 ID
+    pub slice_ids: Vec<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct Earnings {
+    pub lines: BTreeMap<String, EarningsLine>,
+    pub billable: u64,
+    pub billable_rounded: u64,
+    pub non_billable: u64,
+    // 计费但找不到适用费率的时长
+    pub unrated: u64,
+    pub total: f64,
+}
+
+impl RateBook {
+    pub fn new(rates: Vec<Rate>) -> Self {
+        RateBook { rates }
+    }
+
+    // 找到某个对象在 time 时刻生效的费率
+    fn effective_rate(&self, target: RateTarget, target_id: u64, time: u64) -> Option<&Rate> {
+        self.rates
+            .iter()
+            .filter(|rate| {
+                rate.target == target && rate.target_id == target_id && rate.effective_from <= time
+            })
+            .max_by_key(|rate| rate.effective_from)
+    }
+
+    // 获取时间切片适用的时薪，标签费率同样作用于其子标签
+    pub fn rate_for(
+        &self,
+        slice: &TimeSlice,
+        project: Option<&Project>,
+        tags: &[Tag],
+    ) -> Option<f64> {
+        let time = slice.start_time;
+        if let Some(rate) =
+            project.and_then(|p| self.effective_rate(RateTarget::Project, p.id, time))
+        {
+            return Some(rate.hourly_rate);
+        }
+
+        let tag_rate = self
+            .rates
+            .iter()
+            .filter(|rate| rate.target == RateTarget::Tag && rate.effective_from <= time)
+            .filter(|rate| {
+                tags.iter()
+                    .any(|tag| is_in_tag_subtree(&tag.name, &rate.target_name))
+            })
+            .max_by_key(|rate| (tag_depth(&rate.target_name), rate.effective_from));
+        if let Some(rate) = tag_rate {
+            return Some(rate.hourly_rate);
+        }
+
+        project
+            .and_then(|p| p.client_id)
+            .and_then(|client_id| self.effective_rate(RateTarget::Client, client_id, time))
+            .map(|rate| rate.hourly_rate)
+    }
+
+    // 计算收入，按项目分组，没有项目时按第一个标签分组
+    pub fn earnings(&self, timeline: &Timeline, rounding: Option<&RoundingPolicy>) -> Earnings {
+        self.earnings_by(timeline, rounding, |slice| {
+            timeline
+                .projects
+                .get(&slice.id)
+                .map(|p| format!("@{}", p.name))
+                .or_else(|| {
+                    timeline
+                        .tags
+                        .get(&slice.id)
+                        .and_then(|tags| tags.first())
+                        .map(|t| format!("#{}", t.name))
+                })
+                .unwrap_or_else(|| "(none)".to_string())
+        })
+    }
+
+    // 按 label 返回的分组计算收入，有取整规则时按取整后的时长计费
+    pub fn earnings_by<F>(
+        &self,
+        timeline: &Timeline,
+        rounding: Option<&RoundingPolicy>,
+        label: F,
+    ) -> Earnings
+    where
+        F: Fn(&TimeSlice) -> String,
+    {
+        let mut earnings = Earnings::default();
+        let mut line_slices: BTreeMap<String, Vec<&TimeSlice>> = BTreeMap::new();
+        let mut billable_slices = vec![];
+        for slice in &timeline.list {
+            let len = slice.get_len();
+            if !slice.billable {
+                earnings.non_billable += len;
+                continue;
+            }
+            earnings.billable += len;
+            billable_slices.push(slice);
+
+            let project = timeline.projects.get(&slice.id);
+            let tags = timeline
+                .tags
+                .get(&slice.id)
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            let Some(rate) = self.rate_for(slice, project, tags) else {
+                earnings.unrated += len;
+                continue;
+            };
+
+            let label = label(slice);
+            let amount = len as f64 / 3_600_000.0 * rate;
+            line_slices.entry(label.clone()).or_default().push(slice);
+            let line = earnings.lines.entry(label).or_insert_with(|| EarningsLine {
+                rate: Some(rate),
+                ..Default::default()
+            });
+            if line.rate != Some(rate) {
+                line.rate = None;
+            }
+            line.duration += len;
+            line.amount += amount;
+            line.slice_ids.push(slice.id);
+        }
+
+        earnings.billable_rounded = earnings.billable;
+        if let Some(rounding) = rounding {
+            earnings.billable_rounded = rounding.rounded_total(timeline, billable_slices);
+        }
+        for (label, line) in earnings.lines.iter_mut() {
+            line.rounded = line.duration;
+            if let Some(rounding) = rounding {
+                line.rounded =
+                    rounding.rounded_total(timeline, line_slices.remove(label).unwrap_or_default());
+                // 取整后的金额按时长比例换算，分组内费率相同时等于取整时长乘以费率
+                if line.duration > 0 {
+                    line.amount *= line.rounded as f64 / line.duration as f64;
+                }
+            }
+            earnings.total += line.amount;
+        }
+        earnings
+    }
+}
+
+// 按给定的小数位数四舍五入金额
+pub fn round_amount(amount: f64, precision: u32) -> f64 {
+    let factor = 10f64.powi(precision as i32);
+    (amount * factor).round() / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3_600_000;
+
+    fn rate(target: RateTarget, target_id: u64, name: &str, hourly_rate: f64, from: u64) -> Rate {
+        Rate {
+            id: 0,
+            target,
+            target_id,
+            target_name: name.to_string(),
+            hourly_rate,
+            effective_from: from,
+        }
+    }
+
+    fn project(id: u64, client_id: Option<u64>) -> Project {
+        Project {
+            id,
+            name: format!("project{id}"),
+            client_id,
+            client: None,
+            archived: false,
+        }
+    }
+
+    fn tags(names: &[&str]) -> Vec<Tag> {
+        names.iter().map(|name| Tag::new(name, None)).collect()
+    }
+
+    fn slice_at(start: u64) -> TimeSlice {
+        TimeSlice::new(1, start, Some(start + HOUR))
+    }
+
+    #[test]
+    fn project_rate_beats_tag_and_client() {
+        let book = RateBook::new(vec![
+            rate(RateTarget::Client, 7, "acme", 50.0, 0),
+            rate(RateTarget::Tag, 3, "client", 80.0, 0),
+            rate(RateTarget::Project, 1, "project1", 100.0, 0),
+        ]);
+        let slice = slice_at(HOUR);
+        let tags = tags(&["client"]);
+        assert_eq!(
+            book.rate_for(&slice, Some(&project(1, Some(7))), &tags),
+            Some(100.0)
+        );
+        // 项目没有费率时使用标签费率，其次是客户费率
+        assert_eq!(
+            book.rate_for(&slice, Some(&project(2, Some(7))), &tags),
+            Some(80.0)
+        );
+        assert_eq!(
+            book.rate_for(&slice, Some(&project(2, Some(7))), &[]),
+            Some(50.0)
+        );
+        assert_eq!(book.rate_for(&slice, Some(&project(2, None)), &[]), None);
+        assert_eq!(book.rate_for(&slice, None, &[]), None);
+    }
+
+    #[test]
+    fn deepest_tag_rate_wins() {
+        let book = RateBook::new(vec![
+            rate(RateTarget::Tag, 1, "client", 80.0, 0),
+            rate(RateTarget::Tag, 2, "client/acme", 120.0, 0),
+            rate(RateTarget::Tag, 3, "client/other", 200.0, 0),
+        ]);
+        let slice = slice_at(HOUR);
+        assert_eq!(
+            book.rate_for(&slice, None, &tags(&["client/acme/backend"])),
+            Some(120.0)
+        );
+        assert_eq!(
+            book.rate_for(&slice, None, &tags(&["client/beta"])),
+            Some(80.0)
+        );
+        // 只是前缀相同的标签不属于该子树
+        assert_eq!(book.rate_for(&slice, None, &tags(&["clientele"])), None);
+    }
+
+    #[test]
+    fn latest_effective_rate_applies() {
+        let book = RateBook::new(vec![
+            rate(RateTarget::Project, 1, "project1", 100.0, 0),
+            rate(RateTarget::Project, 1, "project1", 120.0, 10 * HOUR),
+            rate(RateTarget::Client, 7, "acme", 60.0, 0),
+            rate(RateTarget::Tag, 1, "client", 90.0, 20 * HOUR),
+        ]);
+        let rated = project(1, Some(7));
+        assert_eq!(
+            book.rate_for(&slice_at(5 * HOUR), Some(&rated), &[]),
+            Some(100.0)
+        );
+        assert_eq!(
+            book.rate_for(&slice_at(10 * HOUR), Some(&rated), &[]),
+            Some(120.0)
+        );
+        // 尚未生效的标签费率不参与比较
+        let other = project(2, Some(7));
+        let tags = tags(&["client"]);
+        assert_eq!(
+            book.rate_for(&slice_at(5 * HOUR), Some(&other), &tags),
+            Some(60.0)
+        );
+        assert_eq!(
+            book.rate_for(&slice_at(20 * HOUR), Some(&other), &tags),
+            Some(90.0)
+        );
+    }
+}
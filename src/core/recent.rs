@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+// 每次使用的权重随时间衰减，经过一个半衰期后减半
+const HALF_LIFE: u64 = 7 * 24 * 60 * 60 * 1000;
+
+// 用过的描述，相同的描述只出现一次
+#[derive(Debug, Clone)]
+pub struct RecentDescription {
+    pub desc: String,
+    pub count: usize,
+    pub last_used: u64,
+    // 每次使用按距今的时间衰减后的权重之和，越大越靠前
+    pub score: f64,
+}
+
+// 按使用频率与距今时间综合排序描述，uses 为描述及其使用时的时间
+pub fn rank_descriptions(uses: &[(String, u64)], now: u64) -> Vec<RecentDescription> {
+    let mut recent: HashMap<&str, RecentDescription> = HashMap::new();
+    for (desc, time) in uses {
+        let desc = desc.trim();
+        if desc.is_empty() {
+            continue;
+        }
+        let age = now.saturating_sub(*time) as f64 / HALF_LIFE as f64;
+        let entry = recent.entry(desc).or_insert_with(|| RecentDescription {
+            desc: desc.to_string(),
+            count: 0,
+            last_used: *time,
+            score: 0.0,
+        });
+        entry.count += 1;
+        entry.last_used = entry.last_used.max(*time);
+        entry.score += 0.5_f64.powf(age);
+    }
+    let mut recent: Vec<RecentDescription> = recent.into_values().collect();
+    recent.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.last_used.cmp(&a.last_used))
+    });
+    recent
+}
@@ -0,0 +1,362 @@
+use super::tag::is_in_tag_subtree;
+use super::timeline::Timeline;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+// 工作时间，例如周一到周五的 09:00 - 18:00，结束时刻早于开始时刻时表示跨越午夜
+#[derive(Debug, Clone)]
+pub struct WorkingHours {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl WorkingHours {
+    // 包含 now 的那段工作时间的开始时刻，不在工作时间内时返回 None
+    pub fn period_start(&self, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let time = now.time();
+        let date = now.date_naive();
+        let start_date = if self.start <= self.end {
+            (time >= self.start && time < self.end).then_some(date)?
+        } else if time >= self.start {
+            date
+        } else if time < self.end {
+            date - Duration::days(1)
+        } else {
+            return None;
+        };
+        if !self.days.contains(&start_date.weekday()) {
+            return None;
+        }
+        start_date
+            .and_time(self.start)
+            .and_local_timezone(now.timezone())
+            .earliest()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReminderSettings {
+    // 工作时间内没有计时器运行多久后提醒（毫秒）
+    pub idle_after: u64,
+    // 计时器运行时长超过该标签通常时长的多少倍时提醒
+    pub long_running_factor: f64,
+    // 同一提醒再次出现的间隔（毫秒）
+    pub repeat: u64,
+    pub working_hours: WorkingHours,
+}
+
+#[derive(Debug, Clone)]
+pub enum Reminder {
+    // 已经有 idle 毫秒没有计时器在运行
+    Idle {
+        idle: u64,
+    },
+    // 计时器已经运行 elapsed 毫秒，而同类记录通常只有 usual 毫秒
+    LongRunning {
+        desc: Option<String>,
+        elapsed: u64,
+        usual: u64,
+    },
+}
+
+// 正在运行的计时器
+#[derive(Debug, Clone)]
+pub struct RunningTimer {
+    pub start_time: u64,
+    pub desc: Option<String>,
+    // 相同标签的记录通常的时长，没有足够的历史记录时为 None
+    pub usual: Option<u64>,
+}
+
+// 检查提醒时的记录状态
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub running: Option<RunningTimer>,
+    // 最近一条记录的结束时间
+    pub last_end: Option<u64>,
+}
+
+// 计算通常时长至少需要的历史记录数
+const MIN_SAMPLES: usize = 3;
+
+// 带有 tags 中任一标签（包括子标签）的已结束记录的时长中位数，没有标签时使用全部记录
+pub fn usual_duration(timeline: &Timeline, tags: &[String]) -> Option<u64> {
+    let mut lens: Vec<u64> = timeline
+        .list
+        .iter()
+        .filter(|slice| slice.end_time.is_some())
+        .filter(|slice| {
+            tags.is_empty()
+                || timeline.tags.get(&slice.id).is_some_and(|slice_tags| {
+                    slice_tags
+                        .iter()
+                        .any(|tag| tags.iter().any(|root| is_in_tag_subtree(&tag.name, root)))
+                })
+        })
+        .map(|slice| slice.get_len())
+        .collect();
+    if lens.len() < MIN_SAMPLES {
+        return None;
+    }
+    lens.sort_unstable();
+    Some(lens[lens.len() / 2])
+}
+
+// 提醒引擎，记录上次提醒的时间，避免同一提醒反复出现
+#[derive(Debug)]
+pub struct ReminderEngine {
+    // 开始检查的时间，空闲时长最早从这里算起
+    started_at: u64,
+    last_idle: Option<u64>,
+    // 上次提醒运行过久的计时器的开始时间与提醒时间
+    last_long_running: Option<(u64, u64)>,
+}
+
+impl ReminderEngine {
+    pub fn new(started_at: u64) -> Self {
+        ReminderEngine {
+            started_at,
+            last_idle: None,
+            last_long_running: None,
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        settings: &ReminderSettings,
+        activity: &Activity,
+        now: DateTime<Tz>,
+    ) -> Option<Reminder> {
+        match &activity.running {
+            Some(running) => {
+                self.last_idle = None;
+                self.check_long_running(settings, running, now.timestamp_millis() as u64)
+            }
+            None => {
+                self.last_long_running = None;
+                self.check_idle(settings, activity, now)
+            }
+        }
+    }
+
+    fn check_idle(
+        &mut self,
+        settings: &ReminderSettings,
+        activity: &Activity,
+        now: DateTime<Tz>,
+    ) -> Option<Reminder> {
+        let work_start = settings.working_hours.period_start(now)?;
+        let now_ms = now.timestamp_millis() as u64;
+        // 空闲时长从上一条记录结束、开始检查与本段工作时间开始三者中最晚的时刻算起
+        let idle_since = activity
+            .last_end
+            .unwrap_or(0)
+            .max(self.started_at)
+            .max(work_start.timestamp_millis() as u64);
+        let idle = now_ms.saturating_sub(idle_since);
+        if idle < settings.idle_after {
+            return None;
+        }
+        if self
+            .last_idle
+            .is_some_and(|last| now_ms.saturating_sub(last) < settings.repeat)
+        {
+            return None;
+        }
+        self.last_idle = Some(now_ms);
+        Some(Reminder::Idle { idle })
+    }
+
+    fn check_long_running(
+        &mut self,
+        settings: &ReminderSettings,
+        running: &RunningTimer,
+        now_ms: u64,
+    ) -> Option<Reminder> {
+        let usual = running.usual?;
+        let elapsed = now_ms.saturating_sub(running.start_time);
+        if (elapsed as f64) < usual as f64 * settings.long_running_factor {
+            return None;
+        }
+        if let Some((start_time, last)) = self.last_long_running
+            && start_time == running.start_time
+            && now_ms.saturating_sub(last) < settings.repeat
+        {
+            return None;
+        }
+        self.last_long_running = Some((running.start_time, now_ms));
+        Some(Reminder::LongRunning {
+            desc: running.desc.clone(),
+            elapsed,
+            usual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    const MINUTE: u64 = 60_000;
+
+    // 周一到周五的 09:00 - 18:00，空闲 30 分钟提醒，超过通常时长两倍提醒，每 20 分钟重复
+    fn settings() -> ReminderSettings {
+        ReminderSettings {
+            idle_after: 30 * MINUTE,
+            long_running_factor: 2.0,
+            repeat: 20 * MINUTE,
+            working_hours: WorkingHours {
+                days: vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ],
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            },
+        }
+    }
+
+    // 2025-02-day 的 hour:minute（UTC），2025-02-10 为星期一
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        Tz::UTC.from_utc_datetime(
+            &NaiveDate::from_ymd_opt(2025, 2, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap(),
+        )
+    }
+
+    fn ms(time: DateTime<Tz>) -> u64 {
+        time.timestamp_millis() as u64
+    }
+
+    fn idle(last_end: Option<u64>) -> Activity {
+        Activity {
+            running: None,
+            last_end,
+        }
+    }
+
+    fn running(start_time: u64, usual: Option<u64>) -> Activity {
+        Activity {
+            running: Some(RunningTimer {
+                start_time,
+                desc: Some("#code refactor".to_string()),
+                usual,
+            }),
+            last_end: None,
+        }
+    }
+
+    #[test]
+    fn idle_counts_from_the_latest_of_last_end_start_and_work_start() {
+        let settings = settings();
+        // 8 点开始检查，空闲时长从 9 点的工作时间开始算起
+        let mut engine = ReminderEngine::new(ms(at(10, 8, 0)));
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 9, 29))
+                .is_none()
+        );
+        assert!(matches!(
+            engine.check(&settings, &idle(None), at(10, 9, 30)),
+            Some(Reminder::Idle { idle }) if idle == 30 * MINUTE
+        ));
+        // 上一条记录 10 点结束，10:20 时还没有空闲够 30 分钟
+        let mut engine = ReminderEngine::new(ms(at(10, 8, 0)));
+        let activity = idle(Some(ms(at(10, 10, 0))));
+        assert!(engine.check(&settings, &activity, at(10, 10, 20)).is_none());
+        assert!(engine.check(&settings, &activity, at(10, 10, 30)).is_some());
+    }
+
+    #[test]
+    fn idle_is_not_reminded_outside_working_hours() {
+        let settings = settings();
+        let mut engine = ReminderEngine::new(0);
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 20, 0))
+                .is_none()
+        );
+        // 2025-02-15 为星期六
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(15, 12, 0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn idle_reminder_repeats_after_the_interval() {
+        let settings = settings();
+        let mut engine = ReminderEngine::new(0);
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 10, 0))
+                .is_some()
+        );
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 10, 19))
+                .is_none()
+        );
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 10, 20))
+                .is_some()
+        );
+        // 启动计时器后重新计算，再次空闲时立即提醒
+        let activity = running(ms(at(10, 10, 21)), None);
+        assert!(engine.check(&settings, &activity, at(10, 10, 21)).is_none());
+        assert!(
+            engine
+                .check(&settings, &idle(None), at(10, 10, 22))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn long_running_timer_is_reminded_once_per_interval() {
+        let settings = settings();
+        let mut engine = ReminderEngine::new(0);
+        let start = ms(at(10, 9, 0));
+        let activity = running(start, Some(30 * MINUTE));
+        assert!(engine.check(&settings, &activity, at(10, 9, 59)).is_none());
+        assert!(matches!(
+            engine.check(&settings, &activity, at(10, 10, 0)),
+            Some(Reminder::LongRunning { elapsed, usual, .. })
+                if elapsed == 60 * MINUTE && usual == 30 * MINUTE
+        ));
+        assert!(engine.check(&settings, &activity, at(10, 10, 10)).is_none());
+        assert!(engine.check(&settings, &activity, at(10, 10, 20)).is_some());
+        // 另一个计时器不受上一个计时器的提醒间隔影响
+        let activity = running(ms(at(10, 9, 30)), Some(20 * MINUTE));
+        assert!(engine.check(&settings, &activity, at(10, 10, 21)).is_some());
+        // 没有足够的历史记录时不提醒
+        let mut engine = ReminderEngine::new(0);
+        assert!(
+            engine
+                .check(&settings, &running(start, None), at(10, 17, 0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn working_hours_across_midnight_start_on_the_previous_day() {
+        let hours = WorkingHours {
+            days: vec![Weekday::Mon],
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        };
+        assert_eq!(hours.period_start(at(11, 3, 0)), Some(at(10, 22, 0)));
+        assert_eq!(hours.period_start(at(10, 23, 0)), Some(at(10, 22, 0)));
+        assert_eq!(hours.period_start(at(10, 12, 0)), None);
+        // 星期二晚上开始的工作时间不在 days 中
+        assert_eq!(hours.period_start(at(11, 23, 0)), None);
+    }
+}
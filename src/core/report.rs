@@ -0,0 +1,173 @@
+use super::calendar::Calendar;
+use super::tag::{tag_depth, tag_leaf};
+use super::timeline::Timeline;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// 去掉 #标签 与 @项目 之后的描述
+pub fn record_title(desc: &str) -> String {
+    desc.split_whitespace()
+        .filter(|word| !word.starts_with('#') && !word.starts_with('@'))
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// 模板报表中的一条记录，时间均为毫秒
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRecord {
+    pub id: u64,
+    pub start: u64,
+    pub end: Option<u64>,
+    pub duration: u64,
+    // 完整的描述，包括 #标签 与 @项目
+    pub desc: String,
+    // 去掉 #标签 与 @项目 之后的描述
+    pub title: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub client: Option<String>,
+    pub billable: bool,
+}
+
+// 某个标签的总时长，子标签的时长汇总到祖先标签中
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTag {
+    pub name: String,
+    pub leaf: String,
+    pub depth: usize,
+    pub duration: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportProject {
+    pub name: String,
+    pub client: Option<String>,
+    pub duration: u64,
+}
+
+// 一天的总时长与当天开始的记录，date 的格式为 YYYY-MM-DD
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDay {
+    pub date: String,
+    pub duration: u64,
+    pub records: Vec<ReportRecord>,
+}
+
+// 提供给报表模板的数据
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportData {
+    // 筛选的时间范围，没有指定时为 None
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub generated_at: u64,
+    pub total: u64,
+    pub records: Vec<ReportRecord>,
+    pub tags: Vec<ReportTag>,
+    pub projects: Vec<ReportProject>,
+    pub days: Vec<ReportDay>,
+}
+
+impl ReportData {
+    pub fn new(
+        timeline: &Timeline,
+        calendar: &Calendar,
+        from: Option<u64>,
+        to: Option<u64>,
+        generated_at: u64,
+    ) -> Self {
+        let records: Vec<ReportRecord> = timeline
+            .list
+            .iter()
+            .map(|slice| {
+                let desc = timeline
+                    .desc
+                    .get(&slice.id)
+                    .map_or("", |desc| desc.trim())
+                    .to_string();
+                let project = timeline.projects.get(&slice.id);
+                ReportRecord {
+                    id: slice.id,
+                    start: slice.start_time,
+                    end: slice.end_time,
+                    duration: slice.get_len(),
+                    title: record_title(&desc),
+                    desc,
+                    tags: timeline
+                        .tags
+                        .get(&slice.id)
+                        .into_iter()
+                        .flatten()
+                        .map(|tag| tag.name.clone())
+                        .collect(),
+                    project: project.map(|project| project.name.clone()),
+                    client: project.and_then(|project| project.client.clone()),
+                    billable: slice.billable,
+                }
+            })
+            .collect();
+
+        let tags = timeline
+            .tag_totals()
+            .into_iter()
+            .map(|(name, duration)| ReportTag {
+                leaf: tag_leaf(&name).to_string(),
+                depth: tag_depth(&name),
+                name,
+                duration,
+            })
+            .collect();
+
+        let mut projects: BTreeMap<&str, ReportProject> = BTreeMap::new();
+        for slice in &timeline.list {
+            if let Some(project) = timeline.projects.get(&slice.id) {
+                projects
+                    .entry(&project.name)
+                    .or_insert_with(|| ReportProject {
+                        name: project.name.clone(),
+                        client: project.client.clone(),
+                        duration: 0,
+                    })
+                    .duration += slice.get_len();
+            }
+        }
+
+        // 每天的时长按日历划分，记录归入开始的那一天
+        let mut days: BTreeMap<String, ReportDay> = BTreeMap::new();
+        for (slice, record) in timeline.list.iter().zip(&records) {
+            let end = slice.end_time.unwrap_or(slice.start_time);
+            for (date, len) in calendar.split_by_day(slice.start_time, end) {
+                let date = date.format("%Y-%m-%d").to_string();
+                days.entry(date.clone())
+                    .or_insert_with(|| ReportDay {
+                        date,
+                        duration: 0,
+                        records: vec![],
+                    })
+                    .duration += len;
+            }
+            let date = calendar
+                .date_of(slice.start_time)
+                .format("%Y-%m-%d")
+                .to_string();
+            days.entry(date.clone())
+                .or_insert_with(|| ReportDay {
+                    date,
+                    duration: 0,
+                    records: vec![],
+                })
+                .records
+                .push(record.clone());
+        }
+
+        ReportData {
+            from,
+            to,
+            generated_at,
+            total: timeline.total_len(),
+            records,
+            tags,
+            projects: projects.into_values().collect(),
+            days: days.into_values().collect(),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use super::calendar::date_at;
+use super::time_slice::TimeSlice;
+use super::timeline::Timeline;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    Nearest,
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(RoundingMode::Up),
+            "down" => Ok(RoundingMode::Down),
+            "nearest" => Ok(RoundingMode::Nearest),
+            _ => Err(format!(
+                "Invalid rounding mode: '{s}'. Expected 'up', 'down' or 'nearest'"
+            )),
+        }
+    }
+}
+
+// 取整的范围：每个时间切片单独取整，或者按天、按项目汇总后再取整
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingScope {
+    Slice,
+    Day,
+    Project,
+}
+
+impl FromStr for RoundingScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "slice" => Ok(RoundingScope::Slice),
+            "day" => Ok(RoundingScope::Day),
+            "project" => Ok(RoundingScope::Project),
+            _ => Err(format!(
+                "Invalid rounding scope: '{s}'. Expected 'slice', 'day' or 'project'"
+            )),
+        }
+    }
+}
+
+// 报表中的时长取整规则，只作用于展示与计费，不修改数据库中的原始时间
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RoundingPolicy {
+    // 取整的单位（毫秒），例如 6 分钟或 15 分钟
+    pub increment: u64,
+    pub mode: RoundingMode,
+    pub scope: RoundingScope,
+    // 按天取整时每天开始的时刻距零点的毫秒数
+    pub day_offset: u64,
+}
+
+impl RoundingPolicy {
+    pub fn round(&self, len: u64) -> u64 {
+        if self.increment == 0 {
+            return len;
+        }
+        let units = match self.mode {
+            RoundingMode::Up => len.div_ceil(self.increment),
+            RoundingMode::Down => len / self.increment,
+            RoundingMode::Nearest => (len + self.increment / 2) / self.increment,
+        };
+        units * self.increment
+    }
+
+    // 计算一组时间切片取整后的总时长，按取整范围分组后分别取整再求和
+    pub fn rounded_total<'a>(
+        &self,
+        timeline: &Timeline,
+        slices: impl IntoIterator<Item = &'a TimeSlice>,
+    ) -> u64 {
+        let mut groups: BTreeMap<String, u64> = BTreeMap::new();
+        for slice in slices {
+            *groups.entry(self.group_key(timeline, slice)).or_insert(0) += slice.get_len();
+        }
+        groups.into_values().map(|len| self.round(len)).sum()
+    }
+
+    fn group_key(&self, timeline: &Timeline, slice: &TimeSlice) -> String {
+        match self.scope {
+            RoundingScope::Slice => slice.id.to_string(),
+            // 按记录时所在时区的日期分组，换个时区查看报表不影响取整结果
+            RoundingScope::Day => {
+                date_at(slice.start_time, slice.get_time_zone(), self.day_offset).to_string()
+            }
+            RoundingScope::Project => timeline
+                .projects
+                .get(&slice.id)
+                .map(|project| project.id.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::project::Project;
+
+    const MINUTE: u64 = 60_000;
+    const DAY: u64 = 24 * 60 * MINUTE;
+
+    fn policy(mode: RoundingMode, scope: RoundingScope) -> RoundingPolicy {
+        RoundingPolicy {
+            increment: 15 * MINUTE,
+            mode,
+            scope,
+            day_offset: 0,
+        }
+    }
+
+    // 每项为 (开始时间, 时长, 项目 ID)，时间按 UTC 记录
+    fn timeline(slices: &[(u64, u64, Option<u64>)]) -> Timeline {
+        let mut timeline = Timeline::new();
+        for (id, &(start, len, project_id)) in slices.iter().enumerate() {
+            let id = id as u64 + 1;
+            let mut slice = TimeSlice::new(id, start, Some(start + len));
+            slice.time_zone = Some("UTC".to_string());
+            timeline.push(slice);
+            if let Some(project_id) = project_id {
+                timeline.projects.insert(
+                    id,
+                    Project {
+                        id: project_id,
+                        name: format!("project{project_id}"),
+                        client_id: None,
+                        client: None,
+                        archived: false,
+                    },
+                );
+            }
+        }
+        timeline
+    }
+
+    fn total(policy: &RoundingPolicy, timeline: &Timeline) -> u64 {
+        policy.rounded_total(timeline, &timeline.list)
+    }
+
+    #[test]
+    fn round_by_mode() {
+        let up = policy(RoundingMode::Up, RoundingScope::Slice);
+        let down = policy(RoundingMode::Down, RoundingScope::Slice);
+        let nearest = policy(RoundingMode::Nearest, RoundingScope::Slice);
+        assert_eq!(up.round(MINUTE), 15 * MINUTE);
+        assert_eq!(up.round(15 * MINUTE), 15 * MINUTE);
+        assert_eq!(up.round(0), 0);
+        assert_eq!(down.round(29 * MINUTE), 15 * MINUTE);
+        assert_eq!(down.round(14 * MINUTE), 0);
+        assert_eq!(nearest.round(7 * MINUTE), 0);
+        assert_eq!(nearest.round(8 * MINUTE), 15 * MINUTE);
+        assert_eq!(nearest.round(22 * MINUTE), 15 * MINUTE);
+        assert_eq!(nearest.round(23 * MINUTE), 30 * MINUTE);
+        let none = RoundingPolicy { increment: 0, ..up };
+        assert_eq!(none.round(7 * MINUTE), 7 * MINUTE);
+    }
+
+    #[test]
+    fn rounded_total_by_scope() {
+        // 第一天两条 10 分钟的记录属于项目 1，第二天一条 10 分钟的记录属于项目 1，一条没有项目
+        let timeline = timeline(&[
+            (0, 10 * MINUTE, Some(1)),
+            (MINUTE * 60, 10 * MINUTE, Some(1)),
+            (DAY, 10 * MINUTE, Some(1)),
+            (DAY + MINUTE * 60, 10 * MINUTE, None),
+        ]);
+        let cases = [
+            (RoundingMode::Up, RoundingScope::Slice, 60),
+            (RoundingMode::Up, RoundingScope::Day, 60),
+            (RoundingMode::Up, RoundingScope::Project, 45),
+            (RoundingMode::Down, RoundingScope::Slice, 0),
+            (RoundingMode::Down, RoundingScope::Day, 30),
+            (RoundingMode::Down, RoundingScope::Project, 30),
+            (RoundingMode::Nearest, RoundingScope::Slice, 60),
+            (RoundingMode::Nearest, RoundingScope::Day, 30),
+            (RoundingMode::Nearest, RoundingScope::Project, 45),
+        ];
+        for (mode, scope, minutes) in cases {
+            assert_eq!(
+                total(&policy(mode, scope), &timeline),
+                minutes * MINUTE,
+                "{mode:?} {scope:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn day_scope_uses_day_offset() {
+        // 每天从 04:00 开始时，凌晨 02:00 的记录算作前一天
+        let timeline = timeline(&[
+            (DAY - 60 * MINUTE, 10 * MINUTE, None),
+            (DAY + 120 * MINUTE, 10 * MINUTE, None),
+        ]);
+        let mut policy = policy(RoundingMode::Down, RoundingScope::Day);
+        assert_eq!(total(&policy, &timeline), 0);
+        policy.day_offset = 240 * MINUTE;
+        assert_eq!(total(&policy, &timeline), 15 * MINUTE);
+    }
+}
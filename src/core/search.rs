@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+// 全文索引中插入在中日韩字符之间的分隔符（零宽空格）
+// FTS5 默认的 unicode61 分词器不会切分连续的汉字，逐字切分后短语查询即可匹配任意子串
+pub const CJK_SEPARATOR: char = '\u{200B}';
+
+// 搜索结果摘要中标记高亮开始与结束的字符，展示时替换为终端颜色
+pub const HIGHLIGHT_START: char = '\u{1}';
+pub const HIGHLIGHT_END: char = '\u{2}';
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub time_slice_id: u64,
+    // 带有高亮标记的描述摘要
+    pub snippet: String,
+    // bm25 相关度，越小越相关
+    pub rank: f64,
+}
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF // 日文假名
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF // 韩文
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x2FA1F)
+}
+
+// 在中日韩字符与相邻文字之间插入分隔符，建立索引和解析查询时都需要调用
+pub fn segment_cjk(text: &str) -> String {
+    let mut segmented = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    for c in text.chars() {
+        if let Some(p) = prev
+            && (is_cjk(p) || is_cjk(c))
+            && p.is_alphanumeric()
+            && c.is_alphanumeric()
+        {
+            segmented.push(CJK_SEPARATOR);
+        }
+        segmented.push(c);
+        prev = Some(c);
+    }
+    segmented
+}
+
+// 去掉索引时插入的分隔符，还原原始文本
+pub fn desegment_cjk(text: &str) -> String {
+    text.chars().filter(|c| *c != CJK_SEPARATOR).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::Database;
+
+    fn database() -> Database {
+        Database::new(":memory:").unwrap()
+    }
+
+    fn add(db: &mut Database, desc: &str) -> u64 {
+        let id = db.insert_time_slice(0, Some(1000), "UTC").unwrap();
+        db.insert_time_slice_description(id, desc).unwrap();
+        id
+    }
+
+    fn ids(db: &Database, query: &str) -> Vec<u64> {
+        let mut ids: Vec<u64> = db
+            .search_descriptions(query)
+            .unwrap()
+            .iter()
+            .map(|hit| hit.time_slice_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn segment_separates_cjk_characters_only() {
+        let sep = CJK_SEPARATOR;
+        assert_eq!(segment_cjk("测试"), format!("测{sep}试"));
+        assert_eq!(segment_cjk("fix测试"), format!("fix{sep}测{sep}试"));
+        assert_eq!(segment_cjk("code review"), "code review");
+        // 空白与标点两侧不插入分隔符
+        assert_eq!(segment_cjk("测 试，ok"), "测 试，ok");
+        assert_eq!(segment_cjk("かな한글"), format!("か{sep}な{sep}한{sep}글"));
+    }
+
+    #[test]
+    fn desegment_restores_the_original_text() {
+        for text in [
+            "",
+            "code review",
+            "测试效果",
+            "#code 修复 login 页面的 bug，明天继续",
+            "日本語のテスト 한국어 mixed123文本",
+        ] {
+            assert_eq!(desegment_cjk(&segment_cjk(text)), text);
+        }
+    }
+
+    #[test]
+    fn search_matches_cjk_substrings() {
+        let mut db = database();
+        let hit = add(&mut db, "#code 今天测试效果很好");
+        add(&mut db, "测量尺寸");
+        let other = add(&mut db, "write tests");
+        assert_eq!(ids(&db, "测试"), vec![hit]);
+        assert_eq!(ids(&db, "效果"), vec![hit]);
+        assert!(ids(&db, "试测").is_empty());
+        assert_eq!(ids(&db, "test*"), vec![other]);
+        let hits = db.search_descriptions("测试").unwrap();
+        let snippet = &hits[0].snippet;
+        assert!(!snippet.contains(CJK_SEPARATOR));
+        assert!(snippet.contains(HIGHLIGHT_START));
+        let plain: String = snippet
+            .chars()
+            .filter(|c| *c != HIGHLIGHT_START && *c != HIGHLIGHT_END)
+            .collect();
+        assert_eq!(plain, "#code 今天测试效果很好");
+    }
+
+    #[test]
+    fn index_follows_edits_and_removals() {
+        let mut db = database();
+        let id = add(&mut db, "测试效果");
+        db.update_time_slice_description(id, "代码评审").unwrap();
+        assert!(ids(&db, "测试").is_empty());
+        assert_eq!(ids(&db, "评审"), vec![id]);
+        db.remove_time_slice(id).unwrap();
+        assert!(ids(&db, "评审").is_empty());
+    }
+
+    #[test]
+    fn index_is_rebuilt_when_out_of_sync() {
+        let path = std::env::temp_dir().join(format!("akashic_log_fts_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path_str = path.to_str().unwrap();
+        let mut db = Database::new(path_str).unwrap();
+        let id = add(&mut db, "测试效果");
+        // 模拟旧版本没有全文索引的数据库
+        db.conn
+            .execute("DELETE FROM time_slice_descriptions_fts", [])
+            .unwrap();
+        assert!(ids(&db, "测试").is_empty());
+        drop(db);
+        let db = Database::new(path_str).unwrap();
+        assert_eq!(ids(&db, "测试"), vec![id]);
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,175 @@
+use super::calendar::Calendar;
+use super::report::record_title;
+use super::timeline::Timeline;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::HashMap;
+
+// 站会摘要中的一项，描述、标签与项目都相同的记录合并为一项
+#[derive(Debug, Clone)]
+pub struct StandupItem {
+    // 去掉 #标签 与 @项目 之后的描述
+    pub title: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub duration: u64,
+    // 最早一条记录的开始时间，用于排序
+    pub first_start: u64,
+}
+
+// today 之前最近的一个工作日，working_days 为空时返回昨天
+pub fn previous_working_day(today: NaiveDate, working_days: &[Weekday]) -> NaiveDate {
+    (1..=7)
+        .map(|days| today - Duration::days(days))
+        .find(|date| working_days.contains(&date.weekday()))
+        .unwrap_or(today - Duration::days(1))
+}
+
+// 合并描述（忽略大小写与多余空格）、标签与项目都相同的记录，按最早开始的时间排序
+// 跨天的记录只计入 date 当天的部分，正在计时的记录在开始的那天列出
+pub fn standup_items(
+    timeline: &Timeline,
+    date: NaiveDate,
+    calendar: &Calendar,
+) -> Vec<StandupItem> {
+    let mut items: Vec<StandupItem> = vec![];
+    let mut index: HashMap<(String, Vec<String>, Option<String>), usize> = HashMap::new();
+    for slice in &timeline.list {
+        let end = slice.end_time.unwrap_or(slice.start_time);
+        let len: u64 = calendar
+            .split_by_day(slice.start_time, end)
+            .into_iter()
+            .filter(|(day, _)| *day == date)
+            .map(|(_, len)| len)
+            .sum();
+        if len == 0 && calendar.date_of(slice.start_time) != date {
+            continue;
+        }
+        let desc = timeline.desc.get(&slice.id).map_or("", |desc| desc.trim());
+        let title = record_title(desc);
+        let mut tags: Vec<String> = timeline
+            .tags
+            .get(&slice.id)
+            .into_iter()
+            .flatten()
+            .map(|tag| tag.name.clone())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        let project = timeline
+            .projects
+            .get(&slice.id)
+            .map(|project| project.name.clone());
+        let key = (title.to_lowercase(), tags.clone(), project.clone());
+        match index.get(&key) {
+            Some(&i) => {
+                let item = &mut items[i];
+                item.duration += len;
+                item.first_start = item.first_start.min(slice.start_time);
+            }
+            None => {
+                index.insert(key, items.len());
+                items.push(StandupItem {
+                    title,
+                    tags,
+                    project,
+                    duration: len,
+                    first_start: slice.start_time,
+                });
+            }
+        }
+    }
+    items.sort_by_key(|item| item.first_start);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time_slice::TimeSlice;
+    use chrono_tz::Tz;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn calendar(split_days: bool) -> Calendar {
+        Calendar {
+            time_zone: Tz::UTC,
+            week_start: Weekday::Mon,
+            day_offset: 0,
+            split_days,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 2, day).unwrap()
+    }
+
+    // 2025-02-day 的 hour 点（UTC）
+    fn at(day: u32, hour: u32) -> u64 {
+        date(day)
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp_millis() as u64
+    }
+
+    // 每项为 (开始时间, 结束时间, 描述)
+    fn timeline(slices: &[(u64, Option<u64>, &str)]) -> Timeline {
+        let mut timeline = Timeline::new();
+        for (id, &(start, end, desc)) in slices.iter().enumerate() {
+            let id = id as u64 + 1;
+            timeline.push(TimeSlice::new(id, start, end));
+            timeline.desc.insert(id, desc.to_string());
+        }
+        timeline
+    }
+
+    #[test]
+    fn previous_working_day_skips_the_weekend() {
+        let weekdays = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        // 2025-02-10 为星期一，2025-02-16 为星期日
+        assert_eq!(previous_working_day(date(10), &weekdays), date(7));
+        assert_eq!(previous_working_day(date(16), &weekdays), date(14));
+        assert_eq!(previous_working_day(date(11), &weekdays), date(10));
+        assert_eq!(previous_working_day(date(10), &[]), date(9));
+    }
+
+    #[test]
+    fn items_only_count_time_on_the_date() {
+        let timeline = timeline(&[
+            // 前一天 22 点到当天 2 点，只计入当天的 2 小时
+            (at(12, 22), Some(at(13, 2)), "deploy"),
+            (at(13, 9), Some(at(13, 10)), "Deploy "),
+            // 当天 23 点到第二天 1 点，只计入当天的 1 小时
+            (at(13, 23), Some(at(14, 1)), "review"),
+            (at(12, 9), Some(at(12, 10)), "yesterday"),
+        ]);
+        let items = standup_items(&timeline, date(13), &calendar(true));
+        let summary: Vec<(&str, u64)> = items
+            .iter()
+            .map(|item| (item.title.as_str(), item.duration))
+            .collect();
+        assert_eq!(summary, vec![("deploy", 3 * HOUR), ("review", HOUR)]);
+    }
+
+    #[test]
+    fn items_follow_the_split_days_setting() {
+        let timeline = timeline(&[(at(12, 22), Some(at(13, 2)), "deploy")]);
+        assert!(standup_items(&timeline, date(13), &calendar(false)).is_empty());
+        let items = standup_items(&timeline, date(12), &calendar(false));
+        assert_eq!(items[0].duration, 4 * HOUR);
+    }
+
+    #[test]
+    fn running_record_is_listed_on_its_start_date() {
+        let timeline = timeline(&[(at(13, 9), None, "coding")]);
+        let items = standup_items(&timeline, date(13), &calendar(true));
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].duration, 0);
+    }
+}
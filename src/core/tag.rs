@@ -1,18 +1,107 @@
 use serde::{Deserialize, Serialize};
 
+// 标签路径的分隔符，例如 client/acme/backend
+pub const TAG_PATH_SEPARATOR: char = '/';
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub id: u64,
+    // 标签的完整路径，例如 client/acme/backend
     pub name: String,
     pub color: Option<String>,
+    pub parent_id: Option<u64>,
 }
 
 impl Tag {
+    #[allow(dead_code)]
     pub fn new(name: &str, color: Option<&str>) -> Self {
         Self {
             id: 0,
             name: name.to_string(),
             color: color.map(|c| c.to_string()),
+            parent_id: None,
+        }
+    }
+}
+
+// 规范化标签路径，去掉多余的分隔符，例如 "/client//acme/" -> "client/acme"
+pub fn normalize_tag_path(path: &str) -> String {
+    path.split(TAG_PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|seg| !seg.is_empty())
+        .collect::<Vec<&str>>()
+        .join(&TAG_PATH_SEPARATOR.to_string())
+}
+
+// 获取标签路径的所有祖先路径（包含自身），从顶层开始
+// client/acme/backend -> [client, client/acme, client/acme/backend]
+pub fn tag_ancestors(path: &str) -> Vec<String> {
+    let mut ancestors = vec![];
+    for (ind, ch) in path.char_indices() {
+        if ch == TAG_PATH_SEPARATOR {
+            ancestors.push(path[..ind].to_string());
         }
     }
+    if !path.is_empty() {
+        ancestors.push(path.to_string());
+    }
+    ancestors
+}
+
+// 获取标签路径的最后一段，用于树形展示
+pub fn tag_leaf(path: &str) -> &str {
+    path.rsplit(TAG_PATH_SEPARATOR).next().unwrap_or(path)
+}
+
+// 标签的层级深度，顶层标签为 0
+pub fn tag_depth(path: &str) -> usize {
+    path.matches(TAG_PATH_SEPARATOR).count()
+}
+
+// 判断标签是否位于 root 标签的子树中（包含 root 自身）
+pub fn is_in_tag_subtree(path: &str, root: &str) -> bool {
+    path == root || (path.starts_with(root) && path[root.len()..].starts_with(TAG_PATH_SEPARATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_removes_extra_separators() {
+        assert_eq!(normalize_tag_path("/client//acme/"), "client/acme");
+        assert_eq!(normalize_tag_path(" client / acme "), "client/acme");
+        assert_eq!(normalize_tag_path("code"), "code");
+        assert_eq!(normalize_tag_path("//"), "");
+    }
+
+    #[test]
+    fn ancestors_start_from_the_top_level() {
+        assert_eq!(
+            tag_ancestors("client/acme/backend"),
+            vec!["client", "client/acme", "client/acme/backend"]
+        );
+        assert_eq!(tag_ancestors("code"), vec!["code"]);
+        assert!(tag_ancestors("").is_empty());
+        assert_eq!(tag_ancestors("客户/测试"), vec!["客户", "客户/测试"]);
+    }
+
+    #[test]
+    fn leaf_and_depth() {
+        assert_eq!(tag_leaf("client/acme/backend"), "backend");
+        assert_eq!(tag_leaf("code"), "code");
+        assert_eq!(tag_depth("client/acme/backend"), 2);
+        assert_eq!(tag_depth("code"), 0);
+    }
+
+    #[test]
+    fn subtree_matches_whole_segments_only() {
+        assert!(is_in_tag_subtree("client", "client"));
+        assert!(is_in_tag_subtree("client/acme", "client"));
+        assert!(is_in_tag_subtree("client/acme/backend", "client/acme"));
+        // 名称前缀相同但不是子标签
+        assert!(!is_in_tag_subtree("clients", "client"));
+        assert!(!is_in_tag_subtree("client/acme2", "client/acme"));
+        assert!(!is_in_tag_subtree("client", "client/acme"));
+    }
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+// 常用任务的模板，例如 standup：15m "#meeting daily standup"，通过 s @standup 开始计时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    pub id: u64,
+    pub name: String,
+    // 计时器的时长（毫秒），None 表示不自动停止
+    pub duration: Option<u64>,
+    // 完整的描述，包括 #标签 与 @项目
+    pub desc: Option<String>,
+}
@@ -1,3 +1,6 @@
+use super::calendar::{parse_time_zone, system_time_zone};
+use chrono_tz::Tz;
+
 // 一个时间切片，代表一个时间段
 
 #[derive(Debug, Clone)]
@@ -5,17 +8,44 @@ pub struct TimeSlice {
     pub id: u64,
     pub start_time: u64,
     pub end_time: Option<u64>,
+    // 是否计费，默认计费
+    pub billable: bool,
+    // 已开票的时间切片记录所属发票
+    pub invoice_id: Option<u64>,
+    // 记录时所在的时区，例如 "Asia/Shanghai"，旧版本的记录没有时区
+    pub time_zone: Option<String>,
+}
+
+// 正在计时的切片，开始计时时写入数据库，停止时补上结束时间
+#[derive(Debug, Clone)]
+pub struct OpenTimeSlice {
+    pub time_slice: TimeSlice,
+    pub desc: Option<String>,
+    // 指定了时长的计时器预定的结束时间
+    pub planned_end: Option<u64>,
 }
 
 impl TimeSlice {
+    #[allow(dead_code)]
     pub fn new(id: u64, start_time: u64, end_time: Option<u64>) -> Self {
         Self {
             id,
             start_time,
             end_time,
+            billable: true,
+            invoice_id: None,
+            time_zone: None,
         }
     }
 
+    // 记录时所在的时区，没有记录时区时视为系统时区
+    pub fn get_time_zone(&self) -> Tz {
+        self.time_zone
+            .as_deref()
+            .and_then(|name| parse_time_zone(name).ok())
+            .unwrap_or_else(system_time_zone)
+    }
+
     // 获取切片的长度，单位为毫秒
     pub fn get_len(&self) -> u64 {
         if let Some(end) = self.end_time {
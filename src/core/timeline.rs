@@ -1,7 +1,8 @@
-use super::tag::Tag;
+use super::project::Project;
+use super::tag::{Tag, is_in_tag_subtree, tag_ancestors};
 use super::time_slice::TimeSlice;
 use anyhow::anyhow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 // 时间线即由时间切片组成的有序列表
 pub struct Timeline {
@@ -10,6 +11,10 @@ pub struct Timeline {
     pub tags: HashMap<u64, Vec<Tag>>,
     // 用于记录时间切片的描述信息
     pub desc: HashMap<u64, String>,
+    // 用于记录时间切片所属的项目
+    pub projects: HashMap<u64, Project>,
+    // 设置了颜色的标签，key 为标签路径
+    pub tag_colors: HashMap<String, String>,
 }
 
 #[derive(Debug)]
@@ -17,20 +22,37 @@ pub struct TimeInfo {
     pub time_slice: TimeSlice,
     pub tag: Option<Vec<Tag>>,
     pub desc: Option<String>,
+    pub project: Option<Project>,
+}
+
+// 时间线的筛选条件，为 None 的条件不参与筛选
+#[derive(Debug, Default, Clone)]
+pub struct TimelineFilter {
+    // 标签子树，例如 client/acme 会包含 client/acme/backend
+    pub tags: Option<Vec<String>>,
+    pub projects: Option<Vec<String>>,
+    pub clients: Option<Vec<String>>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
 }
 
 impl Timeline {
+    #[allow(dead_code)]
     pub fn new() -> Self {
         Timeline {
             list: vec![],
             tags: HashMap::new(),
             desc: HashMap::new(),
+            projects: HashMap::new(),
+            tag_colors: HashMap::new(),
         }
     }
 
+    #[allow(dead_code)]
     pub fn init(&mut self) {}
 
     // 向时间线中添加一个时间切片，保持时间线的有序性
+    #[allow(dead_code)]
     pub fn add(&mut self, time_slice: TimeSlice) {
         let pos = self
             .list
@@ -39,11 +61,13 @@ impl Timeline {
         self.list.insert(pos, time_slice);
     }
 
+    #[allow(dead_code)]
     pub fn remove(&mut self, ind: u64) {
         self.list.remove(ind as usize);
     }
 
     // 在时间线尾部插入一个时间切片
+    #[allow(dead_code)]
     pub fn push(&mut self, time_slice: TimeSlice) {
         self.list.push(time_slice);
     }
@@ -56,10 +80,183 @@ impl Timeline {
             .ok_or_else(|| anyhow!("Time slice not found"))?;
         let tag = self.tags.get(&time_slice_id).cloned();
         let desc = self.desc.get(&time_slice_id).cloned();
+        let project = self.projects.get(&time_slice_id).cloned();
         Ok(TimeInfo {
             time_slice: time_slice.clone(),
             tag,
             desc,
+            project,
         })
     }
+
+    // 只保留满足条件的时间切片
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Timeline, &TimeSlice) -> bool,
+    {
+        let ids: BTreeSet<u64> = self
+            .list
+            .iter()
+            .filter(|slice| f(self, slice))
+            .map(|slice| slice.id)
+            .collect();
+        self.list.retain(|slice| ids.contains(&slice.id));
+        self.tags.retain(|id, _| ids.contains(id));
+        self.desc.retain(|id, _| ids.contains(id));
+        self.projects.retain(|id, _| ids.contains(id));
+    }
+
+    pub fn apply_filter(&mut self, filter: &TimelineFilter) {
+        if let Some(tags) = &filter.tags {
+            self.filter_by_tags(tags);
+        }
+        if let Some(projects) = &filter.projects {
+            self.retain(|timeline, slice| {
+                timeline
+                    .projects
+                    .get(&slice.id)
+                    .is_some_and(|project| projects.contains(&project.name))
+            });
+        }
+        if let Some(clients) = &filter.clients {
+            self.retain(|timeline, slice| {
+                timeline
+                    .projects
+                    .get(&slice.id)
+                    .and_then(|project| project.client.as_ref())
+                    .is_some_and(|client| clients.contains(client))
+            });
+        }
+        if filter.start_time.is_some() || filter.end_time.is_some() {
+            self.filter_by_time(filter.start_time, filter.end_time);
+        }
+    }
+
+    // 按标签子树筛选，切片的任意标签位于任意一个 roots 子树中即保留
+    pub fn filter_by_tags(&mut self, roots: &[String]) {
+        self.retain(|timeline, slice| {
+            timeline.tags.get(&slice.id).is_some_and(|tags| {
+                tags.iter()
+                    .any(|tag| roots.iter().any(|root| is_in_tag_subtree(&tag.name, root)))
+            })
+        });
+    }
+
+    // 按时间范围筛选，保留与 [start, end) 有交集的切片
+    pub fn filter_by_time(&mut self, start: Option<u64>, end: Option<u64>) {
+        self.retain(|_, slice| {
+            let slice_end = slice.end_time.unwrap_or(u64::MAX);
+            start.is_none_or(|start| slice_end > start)
+                && end.is_none_or(|end| slice.start_time < end)
+        });
+    }
+
+    // 获取每个标签下的时间切片，子标签的切片同时归入所有祖先标签
+    // 同一个切片在同一个祖先标签下只出现一次
+    pub fn tag_slices(&self) -> BTreeMap<String, Vec<&TimeSlice>> {
+        let mut tag_slices: BTreeMap<String, Vec<&TimeSlice>> = BTreeMap::new();
+        for slice in &self.list {
+            let paths: BTreeSet<String> = self
+                .tags
+                .get(&slice.id)
+                .into_iter()
+                .flatten()
+                .flat_map(|tag| tag_ancestors(&tag.name))
+                .collect();
+            for path in paths {
+                tag_slices.entry(path).or_default().push(slice);
+            }
+        }
+        tag_slices
+    }
+
+    // 统计每个标签的总时长（毫秒），子标签的时长会汇总到所有祖先标签中
+    pub fn tag_totals(&self) -> BTreeMap<String, u64> {
+        self.tag_slices()
+            .into_iter()
+            .map(|(path, slices)| (path, slices.iter().map(|slice| slice.get_len()).sum()))
+            .collect()
+    }
+
+    // 时间线中所有切片的总时长（毫秒）
+    pub fn total_len(&self) -> u64 {
+        self.list.iter().map(|slice| slice.get_len()).sum()
+    }
+
+    // 统计每个项目的总时长（毫秒），key 为项目 ID
+    pub fn project_totals(&self) -> BTreeMap<u64, u64> {
+        let mut totals = BTreeMap::new();
+        for slice in &self.list {
+            if let Some(project) = self.projects.get(&slice.id) {
+                *totals.entry(project.id).or_insert(0) += slice.get_len();
+            }
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    // 每项为 (时长（小时）, 标签)
+    fn timeline(slices: &[(u64, &[&str])]) -> Timeline {
+        let mut timeline = Timeline::new();
+        let mut start = 0;
+        for (id, &(hours, tags)) in slices.iter().enumerate() {
+            let id = id as u64 + 1;
+            timeline.push(TimeSlice::new(id, start, Some(start + hours * HOUR)));
+            timeline
+                .tags
+                .insert(id, tags.iter().map(|tag| Tag::new(tag, None)).collect());
+            start += hours * HOUR;
+        }
+        timeline
+    }
+
+    #[test]
+    fn tag_totals_roll_up_into_ancestors() {
+        let timeline = timeline(&[
+            (2, &["client/acme/backend"]),
+            (1, &["client/acme/frontend"]),
+            (3, &["personal"]),
+            (1, &[]),
+        ]);
+        let totals: Vec<(String, u64)> = timeline.tag_totals().into_iter().collect();
+        let expected = [
+            ("client", 3),
+            ("client/acme", 3),
+            ("client/acme/backend", 2),
+            ("client/acme/frontend", 1),
+            ("personal", 3),
+        ];
+        assert_eq!(
+            totals,
+            expected
+                .iter()
+                .map(|(path, hours)| (path.to_string(), hours * HOUR))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(timeline.total_len(), 7 * HOUR);
+    }
+
+    #[test]
+    fn tag_totals_count_a_slice_once_per_ancestor() {
+        // 同一条记录的两个标签有共同的祖先，祖先只计一次
+        let timeline = timeline(&[(2, &["client/acme", "client/globex"])]);
+        let totals = timeline.tag_totals();
+        assert_eq!(totals["client"], 2 * HOUR);
+        assert_eq!(totals["client/acme"], 2 * HOUR);
+        assert_eq!(totals["client/globex"], 2 * HOUR);
+    }
+
+    #[test]
+    fn filter_by_tags_keeps_the_subtree() {
+        let mut timeline = timeline(&[(1, &["client/acme"]), (1, &["clients"]), (1, &["client"])]);
+        timeline.filter_by_tags(&["client".to_string()]);
+        let ids: Vec<u64> = timeline.list.iter().map(|slice| slice.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
 }
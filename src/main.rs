@@ -3,6 +3,6 @@ mod core;
 mod server;
 use cli::cli::cli_app;
 
-fn main() {
-    cli_app();
+fn main() -> anyhow::Result<()> {
+    cli_app()
 }